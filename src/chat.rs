@@ -4,6 +4,18 @@ use serde::de::{Visitor, Error, IntoDeserializer, MapAccess};
 use serde::ser::SerializeMap;
 use serde_json::Value;
 use crate::{SerializeResult, DeserializeResult};
+use crate::nbt::{Tag, snbt};
+use crate::uuid::UUID4;
+
+mod translate;
+pub use translate::*;
+mod ansi;
+pub use ansi::*;
+mod html;
+mod builder;
+mod markup;
+mod width;
+pub use width::*;
 
 pub type BoxedChat = Box<Chat>;
 
@@ -13,6 +25,8 @@ pub enum Chat {
     Translation(TranslationComponent),
     Keybind(KeybindComponent),
     Score(ScoreComponent),
+    Selector(SelectorComponent),
+    Nbt(NbtComponent),
 }
 
 impl Chat {
@@ -24,6 +38,21 @@ impl Chat {
             Translation(body) => &body.base,
             Keybind(body) => &body.base,
             Score(body) => &body.base,
+            Selector(body) => &body.base,
+            Nbt(body) => &body.base,
+        }
+    }
+
+    pub fn base_mut(&mut self) -> &mut BaseComponent {
+        use Chat::*;
+
+        match self {
+            Text(body) => &mut body.base,
+            Translation(body) => &mut body.base,
+            Keybind(body) => &mut body.base,
+            Score(body) => &mut body.base,
+            Selector(body) => &mut body.base,
+            Nbt(body) => &mut body.base,
         }
     }
 
@@ -43,27 +72,187 @@ impl Chat {
     }
 
     pub fn from_traditional(orig: &str, translate_colorcodes: bool) -> Chat {
-        TraditionalParser::new(orig, translate_colorcodes).parse()
+        Chat::from_legacy(orig, if translate_colorcodes { '&' } else { SECTION_SYMBOL })
+    }
+
+    // like from_traditional, but codes can start with any prefix (as well as §), and hex colors can be written as
+    // <prefix>#RRGGBB or <prefix>x<prefix>R<prefix>R<prefix>G<prefix>G<prefix>B<prefix>B
+    pub fn from_legacy(orig: &str, prefix: char) -> Chat {
+        TraditionalParser::new(orig, prefix).parse()
     }
 
     pub fn to_traditional(&self) -> Option<String> {
+        self.to_legacy(SECTION_SYMBOL, LegacyHexFormat::Downsample)
+    }
+
+    pub fn to_legacy(&self, prefix: char, hex: LegacyHexFormat) -> Option<String> {
         use Chat::*;
 
         match self {
-            Text(body) => Some(body.to_traditional()),
+            Text(body) => Some(body.to_legacy(prefix, hex)),
             _ => None
         }
     }
+
+    // replaces every hex color in this component (and its children) with the closest legacy color
+    pub fn downsample_colors(&mut self) {
+        let base = self.base_mut();
+        if let Some(color) = base.color.as_mut() {
+            *color = ChatColor::Legacy(color.to_legacy());
+        }
+
+        match base.hover_event.as_mut() {
+            Some(ChatHoverEvent::ShowText(text)) => text.downsample_colors(),
+            Some(ChatHoverEvent::ShowEntity(HoverEntity { name: Some(name), .. })) => name.downsample_colors(),
+            _ => {}
+        }
+
+        for child in base.extra.iter_mut() {
+            child.downsample_colors();
+        }
+
+        if let Chat::Translation(body) = self {
+            for with in body.with.iter_mut() {
+                with.downsample_colors();
+            }
+        }
+    }
+
+    // clients before 1.16 (protocol 735) don't understand hex colors, so they get the closest legacy color instead
+    pub fn for_protocol_version(&self, protocol_version: i32) -> Chat {
+        let mut out = self.clone();
+        if protocol_version < HEX_COLOR_PROTOCOL_VERSION {
+            out.downsample_colors();
+        }
+
+        out
+    }
+
+    // the json to send to a client speaking protocol_version, with hover events in the form that version expects
+    pub fn to_versioned_json(&self, protocol_version: i32) -> Value {
+        let chat = self.for_protocol_version(protocol_version);
+        let mut out = serde_json::to_value(&chat).expect("chat is valid json");
+        if protocol_version < HOVER_CONTENTS_PROTOCOL_VERSION {
+            legacy_hover_events(&chat, &mut out, protocol_version);
+        }
+
+        out
+    }
+
+    // sets every style field of every component to the value it is displayed with
+    pub fn resolve_styles(&mut self) {
+        self.resolve_styles_from(&BaseComponent::resolved_root());
+    }
+
+    fn resolve_styles_from(&mut self, parent: &BaseComponent) {
+        let base = self.base_mut();
+        base.inherit_from(parent);
+        let style = base.clone_style();
+        for child in base.extra.iter_mut() {
+            child.resolve_styles_from(&style);
+        }
+
+        if let Chat::Translation(body) = self {
+            for with in body.with.iter_mut() {
+                with.resolve_styles_from(&style);
+            }
+        }
+    }
+
+    // every component in display order, with its style resolved and its children moved out after it
+    pub fn flatten(&self) -> Vec<Chat> {
+        let mut resolved = self.clone();
+        resolved.resolve_styles();
+        let mut out = Vec::new();
+        resolved.flatten_into(&mut out);
+        out
+    }
+
+    fn flatten_into(mut self, to: &mut Vec<Chat>) {
+        let extra = core::mem::take(&mut self.base_mut().extra);
+        if !matches!(&self, Chat::Text(body) if body.text.is_empty()) {
+            to.push(self);
+        }
+
+        for child in extra {
+            child.flatten_into(to);
+        }
+    }
+
+    // the smallest equivalent tree: flattened, with neighbours of the same style merged and default styles dropped
+    pub fn compact(&self) -> Chat {
+        let mut parts: Vec<Chat> = Vec::new();
+        for mut next in self.flatten() {
+            next.base_mut().strip_defaults();
+            if let (Some(Chat::Text(prev)), Chat::Text(body)) = (parts.last_mut(), &next) {
+                if prev.base.has_same_style_as(&body.base) {
+                    prev.text.push_str(body.text.as_str());
+                    continue;
+                }
+            }
+
+            parts.push(next);
+        }
+
+        if parts.len() == 1 {
+            parts.pop().expect("checked")
+        } else {
+            let mut out = Chat::from_text("");
+            out.base_mut().extra = parts.into_iter().map(Chat::boxed).collect();
+            out
+        }
+    }
+
+    fn collect_text(&self, to: &mut String) {
+        if let Chat::Text(body) = self {
+            to.push_str(body.text.as_str());
+        }
+
+        for child in self.siblings() {
+            child.collect_text(to);
+        }
+    }
+}
+
+pub const HEX_COLOR_PROTOCOL_VERSION: i32 = 735;
+
+pub const HOVER_CONTENTS_PROTOCOL_VERSION: i32 = 735;
+
+// walks the serialized json alongside the component it came from, rewriting each hover event
+fn legacy_hover_events(chat: &Chat, json: &mut Value, protocol_version: i32) {
+    let obj = match json.as_object_mut() {
+        Some(obj) => obj,
+        None => return,
+    };
+
+    let base = chat.base();
+    if let Some(hover) = &base.hover_event {
+        obj.insert("hoverEvent".to_owned(), hover.to_versioned_json(protocol_version));
+    }
+
+    if let Some(Value::Array(extra)) = obj.get_mut("extra") {
+        for (child, child_json) in base.extra.iter().zip(extra.iter_mut()) {
+            legacy_hover_events(child, child_json, protocol_version);
+        }
+    }
+
+    if let Chat::Translation(body) = chat {
+        if let Some(Value::Array(with)) = obj.get_mut("with") {
+            for (child, child_json) in body.with.iter().zip(with.iter_mut()) {
+                legacy_hover_events(child, child_json, protocol_version);
+            }
+        }
+    }
 }
 
 struct TraditionalParser {
     source: Vec<char>,
     at: usize,
-    translate_colorcodes: bool,
+    prefix: char,
 
     // state
     text: String,
-    color: Option<ColorCode>,
+    color: Option<ChatColor>,
     bold: bool,
     italic: bool,
     underlined: bool,
@@ -76,11 +265,11 @@ struct TraditionalParser {
 
 impl TraditionalParser {
 
-    fn new(source: &str, translate_colorcodes: bool) -> Self {
+    fn new(source: &str, prefix: char) -> Self {
         Self {
             source: source.chars().collect(),
             at: 0,
-            translate_colorcodes,
+            prefix,
 
             text: String::new(),
             color: None,
@@ -96,7 +285,9 @@ impl TraditionalParser {
 
     fn parse(mut self) -> Chat {
         loop {
-            if let Some(formatter) = self.consume_formatter() {
+            if let Some(color) = self.consume_hex_color() {
+                self.handle_color(color)
+            } else if let Some(formatter) = self.consume_formatter() {
                 self.handle_formatter(formatter)
             } else if let Some(next) = self.consume_char() {
                 self.push_next(next)
@@ -114,17 +305,24 @@ impl TraditionalParser {
         }
 
         match formatter {
-            Color(color) => {
-                self.finish_current();
-                self.color = Some(color);
-            }
+            Color(color) => self.handle_color(ChatColor::Legacy(color)),
             Obfuscated => self.obfuscated = true,
             Bold => self.bold = true,
             Strikethrough => self.strikethrough = true,
             Underline => self.underlined = true,
             Italic => self.italic = true,
-            _ => {}
+            Reset => self.reset_style(),
+        }
+    }
+
+    // colors turn off any formatting that came before them
+    fn handle_color(&mut self, color: ChatColor) {
+        if self.has_text() {
+            self.finish_current();
         }
+
+        self.reset_style();
+        self.color = Some(color);
     }
 
     fn push_next(&mut self, next: char) {
@@ -136,12 +334,13 @@ impl TraditionalParser {
             let current = TextComponent {
                 text: self.text.clone(),
                 base: BaseComponent {
-                    color: self.color.clone(),
-                    bold: self.bold,
-                    italic: self.italic,
-                    underlined: self.underlined,
-                    strikethrough: self.strikethrough,
-                    obfuscated: self.obfuscated,
+                    color: self.color,
+                    font: None,
+                    bold: flag_if_set(self.bold),
+                    italic: flag_if_set(self.italic),
+                    underlined: flag_if_set(self.underlined),
+                    strikethrough: flag_if_set(self.strikethrough),
+                    obfuscated: flag_if_set(self.obfuscated),
                     hover_event: None,
                     click_event: None,
                     insertion: None,
@@ -151,8 +350,6 @@ impl TraditionalParser {
             self.text.clear();
             self.done.push(current);
         }
-
-        self.reset_style();
     }
 
     fn reset_style(&mut self) {
@@ -169,10 +366,38 @@ impl TraditionalParser {
     }
 
     fn is_on_formatter(&self) -> bool {
-        self.source.get(self.at).map(move |c| {
-            let c = *c;
-            c == SECTION_SYMBOL || (self.translate_colorcodes && c == '&')
-        }).unwrap_or(false)
+        self.is_prefix_at(self.at)
+    }
+
+    fn is_prefix_at(&self, at: usize) -> bool {
+        self.source.get(at).map(move |c| *c == SECTION_SYMBOL || *c == self.prefix).unwrap_or(false)
+    }
+
+    // either <prefix>#RRGGBB or <prefix>x followed by six <prefix><hex digit> pairs
+    fn consume_hex_color(&mut self) -> Option<ChatColor> {
+        if !self.is_on_formatter() {
+            return None;
+        }
+
+        let (digits, len): (String, usize) = match self.source.get(self.at + 1) {
+            Some('#') => (self.source.iter().skip(self.at + 2).take(6).collect(), 8),
+            Some('x') | Some('X') => {
+                let mut digits = String::new();
+                for idx in 0..6 {
+                    let at = self.at + 2 + idx * 2;
+                    if !self.is_prefix_at(at) {
+                        return None;
+                    }
+                    digits.push(*self.source.get(at + 1)?);
+                }
+                (digits, 14)
+            }
+            _ => return None,
+        };
+
+        let color = ChatColor::from_name(format!("#{}", digits).as_str())?;
+        self.at += len;
+        Some(color)
     }
 
     fn consume_char(&mut self) -> Option<char> {
@@ -184,16 +409,12 @@ impl TraditionalParser {
         }
     }
 
+    // leaves the prefix to be read as text when it isn't followed by a valid code
     fn consume_formatter(&mut self) -> Option<Formatter> {
         if self.is_on_formatter() {
-            self.consume_char()?;
-            let c = self.consume_char()?;
-            let out = Formatter::from_code(&c);
-            if out.is_none() {
-                self.at -= 1;
-            }
-
-            out
+            let out = Formatter::from_code(self.source.get(self.at + 1)?)?;
+            self.at += 2;
+            Some(out)
         } else {
             None
         }
@@ -247,25 +468,28 @@ impl TraditionalParser {
     }
 }
 
+// style fields left as None are inherited from the parent component, Some(false) explicitly turns a flag off
 #[derive(Serialize, Clone, Debug, PartialEq)]
 pub struct BaseComponent {
-    #[serde(skip_serializing_if = "should_skip_flag_field")]
-    pub bold: bool,
-    #[serde(skip_serializing_if = "should_skip_flag_field")]
-    pub italic: bool,
-    #[serde(skip_serializing_if = "should_skip_flag_field")]
-    pub underlined: bool,
-    #[serde(skip_serializing_if = "should_skip_flag_field")]
-    pub strikethrough: bool,
-    #[serde(skip_serializing_if = "should_skip_flag_field")]
-    pub obfuscated: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub color: Option<ColorCode>,
+    pub bold: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub insertion: Option<String>,
+    pub italic: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub click_event: Option<ChatClickEvent>,
+    pub underlined: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strikethrough: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub obfuscated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<ChatColor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub insertion: Option<String>,
+    #[serde(rename = "clickEvent", skip_serializing_if = "Option::is_none")]
+    pub click_event: Option<ChatClickEvent>,
+    #[serde(rename = "hoverEvent", skip_serializing_if = "Option::is_none")]
     pub hover_event: Option<ChatHoverEvent>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub extra: Vec<BoxedChat>,
@@ -275,6 +499,14 @@ fn should_skip_flag_field(flag: &bool) -> bool {
     !*flag
 }
 
+fn flag_if_set(flag: bool) -> Option<bool> {
+    if flag {
+        Some(true)
+    } else {
+        None
+    }
+}
+
 impl BaseComponent {
 
     fn has_same_style_as(&self, other: &Self) -> bool {
@@ -283,19 +515,161 @@ impl BaseComponent {
             other.underlined == self.underlined &&
             other.strikethrough == self.strikethrough &&
             other.obfuscated == self.obfuscated &&
-            other.color.eq(&self.color)
+            other.color.eq(&self.color) &&
+            other.font.eq(&self.font) &&
+            other.insertion.eq(&self.insertion) &&
+            other.click_event.eq(&self.click_event) &&
+            other.hover_event.eq(&self.hover_event)
+    }
+
+    // a copy of everything except the children
+    fn clone_style(&self) -> BaseComponent {
+        BaseComponent {
+            bold: self.bold,
+            italic: self.italic,
+            underlined: self.underlined,
+            strikethrough: self.strikethrough,
+            obfuscated: self.obfuscated,
+            color: self.color,
+            font: self.font.clone(),
+            insertion: self.insertion.clone(),
+            click_event: self.click_event.clone(),
+            hover_event: self.hover_event.clone(),
+            extra: Vec::new(),
+        }
+    }
+
+    // fills in everything this component would inherit from parent, which must already be resolved
+    fn inherit_from(&mut self, parent: &BaseComponent) {
+        self.bold = self.bold.or(parent.bold);
+        self.italic = self.italic.or(parent.italic);
+        self.underlined = self.underlined.or(parent.underlined);
+        self.strikethrough = self.strikethrough.or(parent.strikethrough);
+        self.obfuscated = self.obfuscated.or(parent.obfuscated);
+        self.color = self.color.or(parent.color);
+        if self.font.is_none() {
+            self.font = parent.font.clone();
+        }
+        if self.insertion.is_none() {
+            self.insertion = parent.insertion.clone();
+        }
+        if self.click_event.is_none() {
+            self.click_event = parent.click_event.clone();
+        }
+        if self.hover_event.is_none() {
+            self.hover_event = parent.hover_event.clone();
+        }
+    }
+
+    // the inverse of inherit_from, assuming the parent is a plain root component
+    fn strip_defaults(&mut self) {
+        for flag in [&mut self.bold, &mut self.italic, &mut self.underlined, &mut self.strikethrough, &mut self.obfuscated].iter_mut() {
+            if **flag == Some(false) {
+                **flag = None;
+            }
+        }
+    }
+
+    fn resolved_root() -> BaseComponent {
+        BaseComponent {
+            bold: Some(false),
+            italic: Some(false),
+            underlined: Some(false),
+            strikethrough: Some(false),
+            obfuscated: Some(false),
+            ..BaseComponent::default()
+        }
+    }
+}
+
+// the style a piece of text is displayed with, once everything it inherits from its parents is applied
+#[derive(Clone, Debug, PartialEq, Default)]
+struct InheritedStyle<'a> {
+    bold: bool,
+    italic: bool,
+    underlined: bool,
+    strikethrough: bool,
+    obfuscated: bool,
+    color: Option<ChatColor>,
+    font: Option<&'a str>,
+    insertion: Option<&'a str>,
+    click_event: Option<&'a ChatClickEvent>,
+    hover_event: Option<&'a ChatHoverEvent>,
+}
+
+impl<'a> InheritedStyle<'a> {
+    fn has_formatting(&self) -> bool {
+        self.bold || self.italic || self.underlined || self.strikethrough || self.obfuscated || self.color.is_some()
+    }
+
+    // whether the two look the same in legacy text, where hex colors may collapse into legacy ones
+    fn has_same_formatting_as(&self, other: &Self, exact_colors: bool) -> bool {
+        let same_color = if exact_colors {
+            self.color == other.color
+        } else {
+            self.color.map(move |c| c.to_legacy()) == other.color.map(move |c| c.to_legacy())
+        };
+
+        self.bold == other.bold &&
+            self.italic == other.italic &&
+            self.underlined == other.underlined &&
+            self.strikethrough == other.strikethrough &&
+            self.obfuscated == other.obfuscated &&
+            same_color
+    }
+
+    fn child(&self, base: &'a BaseComponent) -> Self {
+        Self {
+            bold: base.bold.unwrap_or(self.bold),
+            italic: base.italic.unwrap_or(self.italic),
+            underlined: base.underlined.unwrap_or(self.underlined),
+            strikethrough: base.strikethrough.unwrap_or(self.strikethrough),
+            obfuscated: base.obfuscated.unwrap_or(self.obfuscated),
+            color: base.color.or(self.color),
+            font: base.font.as_deref().or(self.font),
+            insertion: base.insertion.as_deref().or(self.insertion),
+            click_event: base.click_event.as_ref().or(self.click_event),
+            hover_event: base.hover_event.as_ref().or(self.hover_event),
+        }
+    }
+}
+
+// calls f with every piece of text in the tree (in display order) and its inherited style, only looking at
+// text components, so anything else should be rendered first
+fn for_each_styled<'a, F>(chat: &'a Chat, parent: &InheritedStyle<'a>, f: &mut F) where F: FnMut(&'a str, &InheritedStyle<'a>) {
+    let text = match chat {
+        Chat::Text(body) => Some(body.text.as_str()),
+        _ => None,
+    };
+
+    for_each_styled_base(chat.base(), text, parent, f);
+}
+
+fn for_each_styled_base<'a, F>(base: &'a BaseComponent, text: Option<&'a str>, parent: &InheritedStyle<'a>, f: &mut F)
+    where F: FnMut(&'a str, &InheritedStyle<'a>)
+{
+    let style = parent.child(base);
+    if let Some(text) = text {
+        if !text.is_empty() {
+            f(text, &style);
+        }
+    }
+
+    for child in base.extra.iter() {
+        for_each_styled(child, &style, f);
     }
 }
 
 impl Into<BaseComponent> for JsonComponentBase {
     fn into(self) -> BaseComponent {
         BaseComponent {
-            bold: self.bold.unwrap_or(false),
-            italic: self.italic.unwrap_or(false),
-            underlined: self.underlined.unwrap_or(false),
-            strikethrough: self.strikethrough.unwrap_or(false),
-            obfuscated: self.obfuscated.unwrap_or(false),
+            bold: self.bold,
+            italic: self.italic,
+            underlined: self.underlined,
+            strikethrough: self.strikethrough,
+            obfuscated: self.obfuscated,
             color: self.color,
+            font: self.font,
             insertion: self.insertion,
             click_event: self.click_event,
             hover_event: self.hover_event,
@@ -311,7 +685,8 @@ struct JsonComponentBase {
     pub underlined: Option<bool>,
     pub strikethrough: Option<bool>,
     pub obfuscated: Option<bool>,
-    pub color: Option<ColorCode>,
+    pub color: Option<ChatColor>,
+    pub font: Option<String>,
     pub insertion: Option<String>,
     #[serde(rename = "clickEvent")]
     pub click_event: Option<ChatClickEvent>,
@@ -327,12 +702,13 @@ struct JsonComponentBase {
 impl Default for BaseComponent {
     fn default() -> Self {
         Self {
-            bold: false,
-            italic: false,
-            underlined: false,
-            strikethrough: false,
-            obfuscated: false,
+            bold: None,
+            italic: None,
+            underlined: None,
+            strikethrough: None,
+            obfuscated: None,
             color: None,
+            font: None,
             insertion: None,
             click_event: None,
             hover_event: None,
@@ -350,74 +726,72 @@ pub struct TextComponent {
     pub base: BaseComponent,
 }
 
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum LegacyHexFormat {
+    // hex colors are written as the closest legacy color
+    Downsample,
+    // &#RRGGBB, using whatever prefix the text is written with
+    Hash,
+    // §x§R§R§G§G§B§B, as understood by BungeeCord and Spigot
+    BungeeCord,
+}
+
 impl TextComponent {
     pub fn to_traditional(&self) -> String {
-        let b = &self.base;
-        let text = &self.text;
-
-        let (mut buf, has_formatters) = if !text.is_empty() {
-            let formatters = self.traditional_formatters(false);
-            let has_formatters = formatters.is_some();
-            let mut buf = formatters.unwrap_or_else(|| String::new());
-            buf.extend(text.chars());
-            (buf, has_formatters)
-        } else {
-            (String::default(), false)
-        };
+        self.to_legacy(SECTION_SYMBOL, LegacyHexFormat::Downsample)
+    }
 
-        let mut last_had_formatters = has_formatters;
-        for extra in b.extra.iter() {
-            if let Chat::Text(child) = extra.as_ref() {
-                match child.traditional_formatters(last_had_formatters) {
-                    Some(child_fmts) => {
-                        last_had_formatters = true;
-                        buf.extend(child_fmts.chars())
-                    },
-                    None => {
-                        last_had_formatters = false;
+    pub fn to_legacy(&self, prefix: char, hex: LegacyHexFormat) -> String {
+        let exact_colors = hex != LegacyHexFormat::Downsample;
+        let mut buf = String::new();
+        let mut last = InheritedStyle::default();
+        for_each_styled_base(&self.base, Some(self.text.as_str()), &InheritedStyle::default(), &mut |text, style| {
+            if !last.has_same_formatting_as(style, exact_colors) {
+                // a color code clears formatting by itself, otherwise we have to reset before turning things off
+                if style.color.is_none() && last.has_formatting() {
+                    buf.push(prefix);
+                    buf.push(Formatter::Reset.code());
+                }
+
+                match (style.color, hex) {
+                    (Some(ChatColor::Rgb(r, g, b)), LegacyHexFormat::Hash) => {
+                        buf.push(prefix);
+                        buf.push_str(format!("#{:02X}{:02X}{:02X}", r, g, b).as_str());
+                    }
+                    (Some(ChatColor::Rgb(r, g, b)), LegacyHexFormat::BungeeCord) => {
+                        buf.push(prefix);
+                        buf.push('x');
+                        for digit in format!("{:02x}{:02x}{:02x}", r, g, b).chars() {
+                            buf.push(prefix);
+                            buf.push(digit);
+                        }
+                    }
+                    (Some(c), _) => {
+                        buf.push(prefix);
+                        buf.push(c.to_legacy().code());
                     }
+                    (None, _) => {}
                 }
 
-                buf.extend(child.text.chars());
+                let mut apply_formatter = |b: bool, formatter: Formatter| {
+                    if b {
+                        buf.push(prefix);
+                        buf.push(formatter.code());
+                    }
+                };
+
+                apply_formatter(style.bold, Formatter::Bold);
+                apply_formatter(style.italic, Formatter::Italic);
+                apply_formatter(style.strikethrough, Formatter::Strikethrough);
+                apply_formatter(style.underlined, Formatter::Underline);
+                apply_formatter(style.obfuscated, Formatter::Obfuscated);
+                last = style.clone();
             }
-        }
 
-        buf
-    }
+            buf.push_str(text);
+        });
 
-    fn traditional_formatters(&self, prev_colored: bool) -> Option<String> {
-        let b = &self.base;
-        let mut buf = String::default();
-
-        if let Some(c) = b.color {
-            buf.push(SECTION_SYMBOL);
-            buf.push(c.code());
-        }
-
-        let mut apply_formatter = |b: bool, formatter: Formatter| {
-            if b {
-                buf.push(SECTION_SYMBOL);
-                buf.push(formatter.code());
-            }
-        };
-
-        apply_formatter(b.bold, Formatter::Bold);
-        apply_formatter(b.italic, Formatter::Italic);
-        apply_formatter(b.strikethrough, Formatter::Strikethrough);
-        apply_formatter(b.underlined, Formatter::Underline);
-        apply_formatter(b.obfuscated, Formatter::Obfuscated);
-
-        if buf.is_empty() {
-            if prev_colored {
-                buf.push(SECTION_SYMBOL);
-                buf.push('r');
-                Some(buf)
-            } else {
-                None
-            }
-        } else {
-            Some(buf)
-        }
+        buf
     }
 }
 
@@ -449,6 +823,34 @@ pub struct ScoreComponent {
     pub base: BaseComponent
 }
 
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct SelectorComponent {
+    pub selector: String,
+
+    #[serde(flatten)]
+    pub base: BaseComponent
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct NbtComponent {
+    pub nbt: String,
+    #[serde(skip_serializing_if = "should_skip_flag_field")]
+    pub interpret: bool,
+    #[serde(flatten)]
+    pub source: NbtComponentSource,
+
+    #[serde(flatten)]
+    pub base: BaseComponent
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NbtComponentSource {
+    Block(String),
+    Entity(String),
+    Storage(String),
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct ScoreComponentObjective {
     pub name: String,
@@ -510,10 +912,13 @@ impl<'de> Deserialize<'de> for ChatClickEvent {
             fn visit_map<A>(self, mut map: A) -> Result<Self::Value, <A as MapAccess<'de>>::Error> where
                 A: MapAccess<'de>
             {
-                let (action, value) = read_event(&mut map)?;
+                let (action, is_contents, value) = read_event(&mut map)?;
+                if is_contents {
+                    return Err(A::Error::custom("click events only have a value, not contents"));
+                }
 
                 use ChatClickEvent::*;
-                match action {
+                match action.as_str() {
                     "open_url" => match value.as_str() {
                         Some(url) => Ok(OpenUrl(url.to_owned())),
                         None => Err(A::Error::custom(format!("open_url requires string body, got {}", value)))
@@ -542,30 +947,208 @@ impl<'de> Deserialize<'de> for ChatClickEvent {
 #[derive(Clone, Debug, PartialEq)]
 pub enum ChatHoverEvent {
     ShowText(BoxedChat),
-    ShowItem(Value),
-    ShowEntity(Value)
+    ShowItem(HoverItem),
+    ShowEntity(HoverEntity)
 }
 
-impl Serialize for ChatHoverEvent {
-    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error> where
-        S: Serializer
-    {
-        let mut m = serializer.serialize_map(Some(2))?;
+#[derive(Clone, Debug, PartialEq)]
+pub struct HoverItem {
+    pub id: String,
+    pub count: i32,
+    pub tag: Option<Tag>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HoverEntity {
+    pub kind: String,
+    pub id: UUID4,
+    pub name: Option<BoxedChat>,
+}
 
+impl ChatHoverEvent {
+    fn action(&self) -> &str {
         use ChatHoverEvent::*;
 
-        m.serialize_entry("action", match self {
+        match self {
             ShowText(_) => "show_text",
             ShowItem(_) => "show_item",
             ShowEntity(_) => "show_entity",
-        })?;
+        }
+    }
 
-        m.serialize_key("value")?;
+    // the 1.16+ form, {"action": ..., "contents": ...}
+    fn contents(&self) -> Value {
+        use ChatHoverEvent::*;
 
         match self {
-            ShowText(body) => m.serialize_value(body),
-            ShowItem(body) => m.serialize_value(body),
-            ShowEntity(body) => m.serialize_value(body),
+            ShowText(body) => serde_json::to_value(body).expect("chat is valid json"),
+            ShowItem(item) => {
+                let mut out = serde_json::Map::new();
+                out.insert("id".to_owned(), Value::String(item.id.clone()));
+                if item.count != 1 {
+                    out.insert("count".to_owned(), Value::from(item.count));
+                }
+                if let Some(tag) = &item.tag {
+                    out.insert("tag".to_owned(), Value::String(snbt::stringify(tag)));
+                }
+                Value::Object(out)
+            }
+            ShowEntity(entity) => {
+                let mut out = serde_json::Map::new();
+                out.insert("type".to_owned(), Value::String(entity.kind.clone()));
+                out.insert("id".to_owned(), Value::String(entity.id.hex()));
+                if let Some(name) = &entity.name {
+                    out.insert("name".to_owned(), serde_json::to_value(name).expect("chat is valid json"));
+                }
+                Value::Object(out)
+            }
+        }
+    }
+
+    // the pre 1.16 form, where items and entities are sent as snbt inside of a text component
+    fn legacy_value(&self, protocol_version: i32) -> Value {
+        use ChatHoverEvent::*;
+
+        match self {
+            ShowText(body) => body.to_versioned_json(protocol_version),
+            ShowItem(item) => {
+                let mut out = Tag::Compound(Vec::new());
+                let _ = out.insert("id", item.id.as_str());
+                // Count is a byte before 1.16, clamp rather than wrap
+                let _ = out.insert("Count", item.count.max(i8::MIN as i32).min(i8::MAX as i32) as i8);
+                if let Some(tag) = &item.tag {
+                    let _ = out.insert("tag", tag.clone());
+                }
+                legacy_snbt_text(&out)
+            }
+            ShowEntity(entity) => {
+                let mut out = Tag::Compound(Vec::new());
+                let _ = out.insert("type", entity.kind.as_str());
+                let _ = out.insert("id", entity.id.hex());
+                if let Some(name) = &entity.name {
+                    let _ = out.insert("name", name.to_versioned_json(protocol_version).to_string());
+                }
+                legacy_snbt_text(&out)
+            }
+        }
+    }
+
+    fn to_versioned_json(&self, protocol_version: i32) -> Value {
+        let mut out = serde_json::Map::new();
+        out.insert("action".to_owned(), Value::String(self.action().to_owned()));
+        if protocol_version < HOVER_CONTENTS_PROTOCOL_VERSION {
+            out.insert("value".to_owned(), self.legacy_value(protocol_version));
+        } else {
+            out.insert("contents".to_owned(), self.contents());
+        }
+
+        Value::Object(out)
+    }
+
+    fn from_contents(action: &str, contents: &Value) -> Result<Self, String> {
+        use ChatHoverEvent::*;
+
+        match action {
+            "show_text" => Ok(ShowText(parse_hover_chat(contents)?.boxed())),
+            "show_item" => {
+                if let Some(id) = contents.as_str() {
+                    return Ok(ShowItem(HoverItem { id: id.to_owned(), count: 1, tag: None }));
+                }
+
+                let id = contents.get("id").and_then(Value::as_str)
+                    .ok_or_else(|| format!("show_item requires a string id, got {}", contents))?;
+                let count = match contents.get("count") {
+                    Some(raw) => raw.as_i64()
+                        .ok_or_else(|| format!("show_item count must be an integer, got {}", raw))? as i32,
+                    None => 1,
+                };
+                let tag = match contents.get("tag") {
+                    Some(raw) => Some(parse_hover_snbt(raw.as_str()
+                        .ok_or_else(|| format!("show_item tag must be a string, got {}", raw))?)?),
+                    None => None,
+                };
+
+                Ok(ShowItem(HoverItem { id: id.to_owned(), count, tag }))
+            }
+            "show_entity" => {
+                let kind = contents.get("type").and_then(Value::as_str)
+                    .ok_or_else(|| format!("show_entity requires a string type, got {}", contents))?;
+                let id = contents.get("id").and_then(Value::as_str).and_then(UUID4::parse)
+                    .ok_or_else(|| format!("show_entity requires a uuid id, got {}", contents))?;
+                let name = match contents.get("name") {
+                    Some(raw) => Some(parse_hover_chat(raw)?.boxed()),
+                    None => None,
+                };
+
+                Ok(ShowEntity(HoverEntity { kind: kind.to_owned(), id, name }))
+            }
+            other => Err(format!("invalid hover action kind {}", other)),
+        }
+    }
+
+    fn from_legacy_value(action: &str, value: &Value) -> Result<Self, String> {
+        use ChatHoverEvent::*;
+
+        if action == "show_text" {
+            return Ok(ShowText(parse_hover_chat(value)?.boxed()));
+        }
+
+        let mut text = String::new();
+        parse_hover_chat(value)?.collect_text(&mut text);
+        let tag = parse_hover_snbt(text.as_str())?;
+        match action {
+            "show_item" => {
+                let id = tag.get_str("id")
+                    .ok_or_else(|| format!("legacy show_item requires a string id, got {}", text))?;
+                let count = match tag.get("Count") {
+                    Some(Tag::Byte(v)) => *v as i32,
+                    Some(Tag::Short(v)) => *v as i32,
+                    Some(Tag::Int(v)) => *v,
+                    _ => 1,
+                };
+
+                Ok(ShowItem(HoverItem { id: id.to_owned(), count, tag: tag.get("tag").cloned() }))
+            }
+            "show_entity" => {
+                let kind = tag.get_str("type")
+                    .ok_or_else(|| format!("legacy show_entity requires a string type, got {}", text))?;
+                let id = tag.get_str("id").and_then(UUID4::parse)
+                    .ok_or_else(|| format!("legacy show_entity requires a uuid id, got {}", text))?;
+                // the name is json, but some servers sent plain text here
+                let name = tag.get_str("name").map(move |raw| {
+                    serde_json::from_str::<Chat>(raw).unwrap_or_else(move |_| Chat::from_text(raw)).boxed()
+                });
+
+                Ok(ShowEntity(HoverEntity { kind: kind.to_owned(), id, name }))
+            }
+            other => Err(format!("invalid hover action kind {}", other)),
+        }
+    }
+}
+
+fn parse_hover_chat(value: &Value) -> Result<Chat, String> {
+    Chat::deserialize(value).map_err(move |err| format!("error deserializing hover text {:?}", err))
+}
+
+fn parse_hover_snbt(text: &str) -> Result<Tag, String> {
+    snbt::parse(text).map_err(move |err| format!("error parsing hover snbt {} :: {}", text, err))
+}
+
+fn legacy_snbt_text(tag: &Tag) -> Value {
+    let mut out = serde_json::Map::new();
+    out.insert("text".to_owned(), Value::String(snbt::stringify(tag)));
+    Value::Object(out)
+}
+
+impl Serialize for ChatHoverEvent {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error> where
+        S: Serializer
+    {
+        let mut m = serializer.serialize_map(Some(2))?;
+        m.serialize_entry("action", self.action())?;
+        match self {
+            ChatHoverEvent::ShowText(body) => m.serialize_entry("contents", body),
+            other => m.serialize_entry("contents", &other.contents()),
         }?;
 
         m.end()
@@ -582,26 +1165,19 @@ impl<'de> Deserialize<'de> for ChatHoverEvent {
             type Value = ChatHoverEvent;
 
             fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                write!(f, "an event object for ChatClickEvent")
+                write!(f, "an event object for ChatHoverEvent")
             }
 
             //noinspection ALL
             fn visit_map<A>(self, mut map: A) -> Result<Self::Value, <A as MapAccess<'de>>::Error> where
                 A: MapAccess<'de>
             {
-                let (action, value) = read_event(&mut map)?;
-
-                use ChatHoverEvent::*;
-                match action {
-                    "show_text" => Ok(ShowText(
-                        Chat::deserialize(value.into_deserializer())
-                            .map_err(move |err| A::Error::custom(
-                                format!("error deserializing text to show {:?}", err)))?
-                            .boxed())),
-                    "show_item" => Ok(ShowItem(value)),
-                    "show_entity" => Ok(ShowEntity(value)),
-                    other => Err(A::Error::custom(format!("invalid hover action kind {}", other)))
-                }
+                let (action, is_contents, value) = read_event(&mut map)?;
+                if is_contents {
+                    ChatHoverEvent::from_contents(action.as_str(), &value)
+                } else {
+                    ChatHoverEvent::from_legacy_value(action.as_str(), &value)
+                }.map_err(A::Error::custom)
             }
         }
 
@@ -697,6 +1273,39 @@ impl ColorCode {
         }
     }
 
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match self {
+            ColorCode::Black => (0x00, 0x00, 0x00),
+            ColorCode::DarkBlue => (0x00, 0x00, 0xAA),
+            ColorCode::DarkGreen => (0x00, 0xAA, 0x00),
+            ColorCode::DarkAqua => (0x00, 0xAA, 0xAA),
+            ColorCode::DarkRed => (0xAA, 0x00, 0x00),
+            ColorCode::DarkPurple => (0xAA, 0x00, 0xAA),
+            ColorCode::Gold => (0xFF, 0xAA, 0x00),
+            ColorCode::Gray => (0xAA, 0xAA, 0xAA),
+            ColorCode::DarkGray => (0x55, 0x55, 0x55),
+            ColorCode::Blue => (0x55, 0x55, 0xFF),
+            ColorCode::Green => (0x55, 0xFF, 0x55),
+            ColorCode::Aqua => (0x55, 0xFF, 0xFF),
+            ColorCode::Red => (0xFF, 0x55, 0x55),
+            ColorCode::LightPurple => (0xFF, 0x55, 0xFF),
+            ColorCode::Yellow => (0xFF, 0xFF, 0x55),
+            ColorCode::White => (0xFF, 0xFF, 0xFF),
+        }
+    }
+
+    pub fn nearest(r: u8, g: u8, b: u8) -> Self {
+        let distance = move |code: &ColorCode| {
+            let (cr, cg, cb) = code.rgb();
+            let dr = cr as i32 - r as i32;
+            let dg = cg as i32 - g as i32;
+            let db = cb as i32 - b as i32;
+            dr * dr + dg * dg + db * db
+        };
+
+        *ALL_COLOR_CODES.iter().min_by_key(move |code| distance(code)).expect("not empty")
+    }
+
     pub fn name(&self) -> &str {
         match self {
             ColorCode::Black => "black",
@@ -760,6 +1369,108 @@ impl<'de> Deserialize<'de> for ColorCode {
     }
 }
 
+const ALL_COLOR_CODES: [ColorCode; 16] = [
+    ColorCode::Black,
+    ColorCode::DarkBlue,
+    ColorCode::DarkGreen,
+    ColorCode::DarkAqua,
+    ColorCode::DarkRed,
+    ColorCode::DarkPurple,
+    ColorCode::Gold,
+    ColorCode::Gray,
+    ColorCode::DarkGray,
+    ColorCode::Blue,
+    ColorCode::Green,
+    ColorCode::Aqua,
+    ColorCode::Red,
+    ColorCode::LightPurple,
+    ColorCode::Yellow,
+    ColorCode::White,
+];
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum ChatColor {
+    Legacy(ColorCode),
+    Rgb(u8, u8, u8),
+}
+
+impl ChatColor {
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(hex) = name.strip_prefix('#') {
+            if hex.len() != 6 || !hex.chars().all(move |c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+
+            let raw = u32::from_str_radix(hex, 16).ok()?;
+            Some(ChatColor::Rgb((raw >> 16) as u8, (raw >> 8) as u8, raw as u8))
+        } else {
+            ColorCode::from_name(name).map(ChatColor::Legacy)
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            ChatColor::Legacy(code) => code.name().to_owned(),
+            ChatColor::Rgb(r, g, b) => format!("#{:02X}{:02X}{:02X}", r, g, b),
+        }
+    }
+
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match self {
+            ChatColor::Legacy(code) => code.rgb(),
+            ChatColor::Rgb(r, g, b) => (*r, *g, *b),
+        }
+    }
+
+    pub fn to_legacy(&self) -> ColorCode {
+        match self {
+            ChatColor::Legacy(code) => *code,
+            ChatColor::Rgb(r, g, b) => ColorCode::nearest(*r, *g, *b),
+        }
+    }
+}
+
+impl From<ColorCode> for ChatColor {
+    fn from(code: ColorCode) -> Self {
+        ChatColor::Legacy(code)
+    }
+}
+
+impl Serialize for ChatColor {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error> where
+        S: Serializer {
+        serializer.serialize_str(self.name().as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ChatColor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, <D as Deserializer<'de>>::Error> where
+        D: Deserializer<'de>
+    {
+        struct V;
+
+        impl<'de> Visitor<'de> for V {
+            type Value = ChatColor;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(fmt, "a color code name or a #RRGGBB hex color")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where
+                E: de::Error,
+            {
+                if let Some(color) = ChatColor::from_name(v) {
+                    Ok(color)
+                } else {
+                    Err(E::custom(format!("invalid color {}", v)))
+                }
+            }
+        }
+
+        deserializer.deserialize_str(V)
+    }
+}
+
 #[derive(PartialOrd, PartialEq, Debug, Copy, Clone)]
 pub enum Formatter {
     Color(ColorCode),
@@ -780,7 +1491,7 @@ impl Formatter {
             'n' => Some(Formatter::Underline),
             'o' => Some(Formatter::Italic),
             'r' => Some(Formatter::Reset),
-            _ => ColorCode::from_code(i).map(Formatter::Color),
+            lower => ColorCode::from_code(&lower).map(Formatter::Color),
         }
     }
 
@@ -860,6 +1571,17 @@ impl<'de> Deserialize<'de> for Chat {
                 }))
             }
 
+            // vanilla treats an array as the first element, with the rest appended to its extra
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: de::SeqAccess<'de> {
+                let mut out: Chat = seq.next_element()?
+                    .ok_or_else(|| A::Error::custom("chat array must not be empty"))?;
+                while let Some(next) = seq.next_element::<Chat>()? {
+                    out.base_mut().extra.push(next.boxed());
+                }
+
+                Ok(out)
+            }
+
             fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error> where M: de::MapAccess<'de> {
                 let mut base: JsonComponentBase = de::Deserialize::deserialize(de::value::MapAccessDeserializer::new(map))?;
                 let additional = &mut base._additional;
@@ -934,7 +1656,47 @@ impl<'de> Deserialize<'de> for Chat {
                     }));
                 }
 
-                // selector (SKIP)
+                // selector
+                if let Some(raw_selector) = additional.remove("selector") {
+                    return if let Some(selector) = raw_selector.as_str() {
+                        Ok(Chat::Selector(SelectorComponent{
+                            selector: selector.to_owned(),
+                            base: base.into(),
+                        }))
+                    } else {
+                        Err(M::Error::custom(format!("have selector but it's not a string! {:?}", raw_selector)))
+                    }
+                }
+
+                // nbt
+                if let Some(raw_nbt) = additional.remove("nbt") {
+                    let nbt = raw_nbt.as_str()
+                        .ok_or_else(|| M::Error::custom(format!("have nbt but it's not a string! {:?}", raw_nbt)))?
+                        .to_owned();
+
+                    let interpret = match additional.remove("interpret") {
+                        Some(raw_interpret) => raw_interpret.as_bool()
+                            .ok_or_else(|| M::Error::custom(format!("have interpret but it's not a bool! {:?}", raw_interpret)))?,
+                        None => false,
+                    };
+
+                    let source = if let Some(raw_block) = additional.remove("block") {
+                        raw_block.as_str().map(move |v| NbtComponentSource::Block(v.to_owned()))
+                    } else if let Some(raw_entity) = additional.remove("entity") {
+                        raw_entity.as_str().map(move |v| NbtComponentSource::Entity(v.to_owned()))
+                    } else if let Some(raw_storage) = additional.remove("storage") {
+                        raw_storage.as_str().map(move |v| NbtComponentSource::Storage(v.to_owned()))
+                    } else {
+                        None
+                    }.ok_or_else(move || M::Error::custom("nbt component needs a string block, entity or storage"))?;
+
+                    return Ok(Chat::Nbt(NbtComponent{
+                        nbt,
+                        interpret,
+                        source,
+                        base: base.into(),
+                    }));
+                }
 
                 Err(M::Error::custom("not able to parse chat component, not a valid chat component kind"))
             }
@@ -954,7 +1716,9 @@ impl Serialize for Chat {
             Text(body) => body.serialize(serializer),
             Translation(body) => body.serialize(serializer),
             Keybind(body) => body.serialize(serializer),
-            Score(body) => body.serialize(serializer)
+            Score(body) => body.serialize(serializer),
+            Selector(body) => body.serialize(serializer),
+            Nbt(body) => body.serialize(serializer),
         }
     }
 }
@@ -966,6 +1730,13 @@ impl super::Serialize for Chat {
                 format!("error while encoding chat :: {:?} -> {:?}", self, err)))?
             .mc_serialize(to)
     }
+
+    // the json has to be built to know how long it is
+    fn serialized_size(&self) -> usize {
+        serde_json::to_string(self)
+            .map(move |json| super::Serialize::serialized_size(&json))
+            .unwrap_or(0)
+    }
 }
 
 impl super::Deserialize for Chat {
@@ -979,6 +1750,65 @@ impl super::Deserialize for Chat {
     }
 }
 
+// chat as it's sent to clients before 1.16 (such as 1.15.2). it reads the same as Chat, but is written with every hex
+// color replaced by the closest legacy color and with hover events in the legacy "value" form
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegacyChat(pub Chat);
+
+const LEGACY_CHAT_PROTOCOL_VERSION: i32 = 578;
+
+impl LegacyChat {
+    fn to_json_string(&self) -> Result<String, super::SerializeErr> {
+        serde_json::to_string(&self.0.to_versioned_json(LEGACY_CHAT_PROTOCOL_VERSION))
+            .map_err(move |err| super::SerializeErr::FailedJsonEncode(
+                format!("error while encoding chat :: {:?} -> {:?}", self.0, err)))
+    }
+}
+
+impl From<Chat> for LegacyChat {
+    fn from(chat: Chat) -> Self {
+        LegacyChat(chat)
+    }
+}
+
+impl From<LegacyChat> for Chat {
+    fn from(chat: LegacyChat) -> Self {
+        chat.0
+    }
+}
+
+impl core::ops::Deref for LegacyChat {
+    type Target = Chat;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl core::ops::DerefMut for LegacyChat {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl super::Serialize for LegacyChat {
+    fn mc_serialize<S: super::Serializer>(&self, to: &mut S) -> SerializeResult {
+        self.to_json_string()?.mc_serialize(to)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.to_json_string()
+            .map(move |json| super::Serialize::serialized_size(&json))
+            .unwrap_or(0)
+    }
+}
+
+impl super::Deserialize for LegacyChat {
+    fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
+        Ok(Chat::mc_deserialize(data)?.map(LegacyChat))
+    }
+}
+
 #[cfg(all(test, feature = "std"))]
 use super::protocol::TestRandom;
 
@@ -990,26 +1820,36 @@ impl TestRandom for Chat {
     }
 }
 
+#[cfg(all(test, feature = "std"))]
+impl TestRandom for LegacyChat {
+    fn test_gen_random() -> Self {
+        LegacyChat(Chat::test_gen_random())
+    }
+}
+
+// reads {"action": ..., "value": ...}, or the 1.16 {"action": ..., "contents": ...} (returns true for contents)
 fn read_event<'de, A>(
     access: &mut A,
-) -> Result<(&'de str, Value), <A as MapAccess<'de>>::Error>
+) -> Result<(String, bool, Value), <A as MapAccess<'de>>::Error>
     where A: MapAccess<'de>
 {
-    let mut action: Option<&str> = None;
+    let mut action: Option<String> = None;
     let mut value: Option<Value> = None;
+    let mut is_contents = false;
     while action.is_none() || value.is_none() {
-        if let Some(key) = access.next_key()? {
-            match key {
+        if let Some(key) = access.next_key::<String>()? {
+            match key.as_str() {
                 "action" => {
                     action = access.next_value()?;
                     if action.is_none() {
                         return Err(A::Error::custom("none for value key=action"));
                     }
                 },
-                "value" => {
+                "value" | "contents" => {
+                    is_contents = key.as_str() == "contents";
                     value = access.next_value()?;
                     if value.is_none() {
-                        return Err(A::Error::custom("none for value key=value"));
+                        return Err(A::Error::custom(format!("none for value key={}", key)));
                     }
                 },
                 other => {
@@ -1021,7 +1861,7 @@ fn read_event<'de, A>(
         }
     }
 
-    Ok((action.expect("checked"), value.expect("checked")))
+    Ok((action.expect("checked"), is_contents, value.expect("checked")))
 }
 
 #[cfg(test)]
@@ -1041,7 +1881,7 @@ pub mod tests {
                         text: "this is red, and ".to_owned(),
                         base: {
                             let mut b = BaseComponent::default();
-                            b.color = Some(ColorCode::Red);
+                            b.color = Some(ColorCode::Red.into());
                             b
                         },
                     }).boxed(),
@@ -1053,8 +1893,8 @@ pub mod tests {
                         text: "yellow".to_owned(),
                         base: {
                             let mut b = BaseComponent::default();
-                            b.color = Some(ColorCode::Yellow);
-                            b.bold = true;
+                            b.color = Some(ColorCode::Yellow.into());
+                            b.bold = Some(true);
                             b
                         }
                     }).boxed()
@@ -1068,4 +1908,294 @@ pub mod tests {
         #[cfg(feature="std")]
         println!("{}", serde_json::to_string_pretty(&out).expect("should serialize fine"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_hex_color_round_trip() {
+        let raw = r##"{"text":"hello","color":"#1A2B3C","font":"minecraft:uniform","extra":[{"text":"world","color":"gold"}]}"##;
+        let chat: Chat = serde_json::from_str(raw).expect("should parse");
+        let base = chat.base();
+        assert_eq!(base.color, Some(ChatColor::Rgb(0x1A, 0x2B, 0x3C)));
+        assert_eq!(base.font.as_deref(), Some("minecraft:uniform"));
+        assert_eq!(chat.siblings()[0].base().color, Some(ChatColor::Legacy(ColorCode::Gold)));
+
+        let out = serde_json::to_string(&chat).expect("should serialize");
+        let reparsed: Chat = serde_json::from_str(out.as_str()).expect("should parse again");
+        assert_eq!(reparsed, chat);
+        assert!(out.contains("\"#1A2B3C\""));
+    }
+
+    #[test]
+    fn test_bad_hex_colors() {
+        assert_eq!(ChatColor::from_name("#12345"), None);
+        assert_eq!(ChatColor::from_name("#+12345"), None);
+        assert_eq!(ChatColor::from_name("#GGGGGG"), None);
+        assert_eq!(ChatColor::from_name("#ff5555"), Some(ChatColor::Rgb(0xFF, 0x55, 0x55)));
+        assert!(serde_json::from_str::<Chat>(r##"{"text":"a","color":"#zz"}"##).is_err());
+    }
+
+    #[test]
+    fn test_nearest_legacy_color() {
+        assert_eq!(ColorCode::nearest(0xFF, 0x55, 0x55), ColorCode::Red);
+        assert_eq!(ColorCode::nearest(0xF0, 0x10, 0x10), ColorCode::DarkRed);
+        assert_eq!(ColorCode::nearest(0x01, 0x02, 0x03), ColorCode::Black);
+        assert_eq!(ColorCode::nearest(0xB0, 0xB0, 0xA0), ColorCode::Gray);
+        assert_eq!(ChatColor::Rgb(0x10, 0x10, 0xB0).to_legacy(), ColorCode::DarkBlue);
+    }
+
+    #[test]
+    fn test_downsample_for_old_protocol() {
+        let mut chat = Chat::from_text("hi");
+        chat.base_mut().color = Some(ChatColor::Rgb(0xFF, 0xB0, 0x00));
+        let mut child = Chat::from_text("there");
+        child.base_mut().color = Some(ChatColor::Rgb(0x00, 0x00, 0x99));
+        chat.base_mut().extra.push(child.boxed());
+
+        assert_eq!(chat.to_traditional().expect("is text").as_str(), "§6hi§1there");
+
+        let old = chat.for_protocol_version(578);
+        assert_eq!(old.base().color, Some(ChatColor::Legacy(ColorCode::Gold)));
+        assert_eq!(old.siblings()[0].base().color, Some(ChatColor::Legacy(ColorCode::DarkBlue)));
+
+        let new = chat.for_protocol_version(753);
+        assert_eq!(new, chat);
+    }
+
+    #[test]
+    fn test_selector_round_trip() {
+        let raw = r#"{"selector":"@a[distance=..5]","color":"aqua","extra":[{"text":"!"}]}"#;
+        let chat: Chat = serde_json::from_str(raw).expect("should parse");
+        if let Chat::Selector(body) = &chat {
+            assert_eq!(body.selector.as_str(), "@a[distance=..5]");
+            assert_eq!(body.base.color, Some(ChatColor::Legacy(ColorCode::Aqua)));
+        } else {
+            panic!("expected selector, got {:?}", chat);
+        }
+
+        let out = serde_json::to_string(&chat).expect("should serialize");
+        assert_eq!(serde_json::from_str::<Chat>(out.as_str()).expect("should parse again"), chat);
+    }
+
+    #[test]
+    fn test_nbt_round_trip() {
+        let cases = [
+            (r#"{"nbt":"Items[0].id","block":"~ ~-1 ~"}"#, NbtComponentSource::Block("~ ~-1 ~".to_owned()), false),
+            (r#"{"nbt":"CustomName","entity":"@s","interpret":true}"#, NbtComponentSource::Entity("@s".to_owned()), true),
+            (r#"{"nbt":"msg","storage":"example:data","bold":true}"#, NbtComponentSource::Storage("example:data".to_owned()), false),
+        ];
+
+        for (raw, source, interpret) in cases.iter() {
+            let chat: Chat = serde_json::from_str(raw).expect("should parse");
+            if let Chat::Nbt(body) = &chat {
+                assert_eq!(&body.source, source);
+                assert_eq!(body.interpret, *interpret);
+            } else {
+                panic!("expected nbt, got {:?}", chat);
+            }
+
+            let out = serde_json::to_string(&chat).expect("should serialize");
+            let expected: Value = serde_json::from_str(raw).expect("valid json");
+            let actual: Value = serde_json::from_str(out.as_str()).expect("valid json");
+            assert_eq!(actual, expected);
+        }
+
+        assert!(serde_json::from_str::<Chat>(r#"{"nbt":"a"}"#).is_err());
+        assert!(serde_json::from_str::<Chat>(r#"{"nbt":"a","block":1}"#).is_err());
+    }
+
+    #[test]
+    fn test_hover_contents_round_trip() {
+        let raw = r#"{"text":"a","hoverEvent":{"action":"show_item","contents":{"id":"minecraft:diamond_sword","count":2,"tag":"{Damage:3}"}},
+            "extra":[{"text":"b","hoverEvent":{"action":"show_entity","contents":{"type":"minecraft:pig","id":"6a3f3a0e-8b7e-4e1b-9c1d-2f5c3d4e5f60","name":{"text":"Pig"}}}}]}"#;
+        let chat: Chat = serde_json::from_str(raw).expect("should parse");
+        assert_eq!(chat.base().hover_event, Some(ChatHoverEvent::ShowItem(HoverItem {
+            id: "minecraft:diamond_sword".to_owned(),
+            count: 2,
+            tag: Some(crate::nbt!("Damage" => 3)),
+        })));
+        assert_eq!(chat.siblings()[0].base().hover_event, Some(ChatHoverEvent::ShowEntity(HoverEntity {
+            kind: "minecraft:pig".to_owned(),
+            id: UUID4::parse("6a3f3a0e-8b7e-4e1b-9c1d-2f5c3d4e5f60").expect("valid uuid"),
+            name: Some(Chat::from_text("Pig").boxed()),
+        })));
+
+        let expected: Value = serde_json::from_str(raw).expect("valid json");
+        assert_eq!(serde_json::to_value(&chat).expect("should serialize"), expected);
+        assert_eq!(chat.to_versioned_json(753), expected);
+    }
+
+    #[test]
+    fn test_hover_legacy_value() {
+        let raw = r#"{"text":"a","hoverEvent":{"action":"show_item","value":{"text":"{id:\"minecraft:stone\",Count:5b,tag:{Damage:1}}"}},
+            "extra":[{"text":"b","hoverEvent":{"action":"show_entity","value":"{type:\"minecraft:cow\",id:\"6a3f3a0e-8b7e-4e1b-9c1d-2f5c3d4e5f60\",name:\"{\\\"text\\\":\\\"Bessie\\\"}\"}"}},
+                     {"text":"c","hoverEvent":{"action":"show_text","value":[{"text":"hi "},{"text":"there"}]}}]}"#;
+        let chat: Chat = serde_json::from_str(raw).expect("should parse");
+        let item = HoverItem { id: "minecraft:stone".to_owned(), count: 5, tag: Some(crate::nbt!("Damage" => 1)) };
+        assert_eq!(chat.base().hover_event, Some(ChatHoverEvent::ShowItem(item)));
+        if let Some(ChatHoverEvent::ShowEntity(entity)) = &chat.siblings()[0].base().hover_event {
+            assert_eq!(entity.kind.as_str(), "minecraft:cow");
+            assert_eq!(entity.name, Some(Chat::from_text("Bessie").boxed()));
+        } else {
+            panic!("expected show_entity");
+        }
+
+        let legacy = chat.to_versioned_json(578);
+        assert_eq!(legacy["hoverEvent"]["value"]["text"], Value::from("{id:\"minecraft:stone\",Count:5b,tag:{Damage:1}}"));
+        assert!(legacy["hoverEvent"].get("contents").is_none());
+        assert_eq!(legacy["extra"][1]["hoverEvent"]["value"]["text"], Value::from("hi "));
+
+        let reparsed: Chat = serde_json::from_value(legacy).expect("legacy form should parse");
+        assert_eq!(reparsed, chat);
+    }
+
+    #[test]
+    fn test_hover_legacy_count_clamped() {
+        let item = |count| ChatHoverEvent::ShowItem(HoverItem { id: "minecraft:stone".to_owned(), count, tag: None });
+        let text = |count| item(count).legacy_value(578)["text"].as_str().expect("is text").to_owned();
+        assert_eq!(text(64), "{id:\"minecraft:stone\",Count:64b}");
+        assert_eq!(text(200), "{id:\"minecraft:stone\",Count:127b}");
+        assert_eq!(text(-300), "{id:\"minecraft:stone\",Count:-128b}");
+    }
+
+    #[test]
+    fn test_hover_errors() {
+        assert!(serde_json::from_str::<Chat>(r#"{"text":"a","hoverEvent":{"action":"show_item","contents":{"count":1}}}"#).is_err());
+        assert!(serde_json::from_str::<Chat>(r#"{"text":"a","hoverEvent":{"action":"show_entity","contents":{"type":"a","id":"nope"}}}"#).is_err());
+        // multi-byte ids used to panic in the uuid parser instead of failing
+        assert!(serde_json::from_str::<Chat>(r#"{"text":"a","hoverEvent":{"action":"show_entity","contents":{"type":"a","id":"aaaaaaaéaaaaaaaaaaaaaaaaaaaaaa"}}}"#).is_err());
+        assert!(serde_json::from_str::<Chat>(r#"{"text":"a","hoverEvent":{"action":"show_entity","value":"{type:\"a\",id:\"aaaaaaaéaaaaaaaaaaaaaaaaaaaaaa\"}"}}"#).is_err());
+        assert!(serde_json::from_str::<Chat>(r#"{"text":"a","hoverEvent":{"action":"show_item","value":"{id:"}}"#).is_err());
+        assert!(serde_json::from_str::<Chat>(r#"{"text":"a","clickEvent":{"action":"open_url","contents":"x"}}"#).is_err());
+    }
+
+    fn styled_tree() -> Chat {
+        serde_json::from_str(r#"{"text":"a","bold":true,"color":"red","extra":[
+            {"text":"b","bold":false},
+            {"text":"c","extra":[{"text":"d","italic":true},{"text":"","color":"blue"}]},
+            {"text":"e","color":"red","bold":true}
+        ]}"#).expect("valid chat")
+    }
+
+    #[test]
+    fn test_explicit_false_survives_round_trip() {
+        let chat = styled_tree();
+        assert_eq!(chat.siblings()[0].base().bold, Some(false));
+        assert_eq!(chat.siblings()[1].base().bold, None);
+
+        let out = serde_json::to_string(&chat).expect("should serialize");
+        assert!(out.contains(r#""bold":false"#));
+        assert_eq!(serde_json::from_str::<Chat>(out.as_str()).expect("should parse"), chat);
+    }
+
+    #[test]
+    fn test_traditional_inherits_style() {
+        let chat = styled_tree();
+        assert_eq!(chat.to_traditional().expect("is text").as_str(), "§c§la§cb§c§lc§c§l§od§c§le");
+    }
+
+    #[test]
+    fn test_resolve_styles() {
+        let mut chat = styled_tree();
+        chat.resolve_styles();
+        let c = &chat.siblings()[1];
+        assert_eq!(c.base().bold, Some(true));
+        assert_eq!(c.base().italic, Some(false));
+        assert_eq!(c.base().color, Some(ColorCode::Red.into()));
+        let d = &c.siblings()[0];
+        assert_eq!(d.base().italic, Some(true));
+        assert_eq!(d.base().bold, Some(true));
+        assert_eq!(chat.siblings()[0].base().bold, Some(false));
+    }
+
+    #[test]
+    fn test_flatten_and_compact() {
+        let chat = styled_tree();
+        let flat = chat.flatten();
+        let texts: Vec<(&str, Option<bool>, Option<bool>)> = flat.iter().map(move |part| match part {
+            Chat::Text(body) => (body.text.as_str(), body.base.bold, body.base.italic),
+            other => panic!("expected text, got {:?}", other),
+        }).collect();
+        assert_eq!(texts, alloc::vec![
+            ("a", Some(true), Some(false)),
+            ("b", Some(false), Some(false)),
+            ("c", Some(true), Some(false)),
+            ("d", Some(true), Some(true)),
+            ("e", Some(true), Some(false)),
+        ]);
+        assert!(flat.iter().all(move |part| part.siblings().is_empty()));
+
+        let compact = chat.compact();
+        let json: Value = serde_json::to_value(&compact).expect("should serialize");
+        let expected: Value = serde_json::from_str(r#"{"text":"","extra":[
+            {"text":"a","bold":true,"color":"red"},
+            {"text":"b","color":"red"},
+            {"text":"c","bold":true,"color":"red"},
+            {"text":"d","bold":true,"italic":true,"color":"red"},
+            {"text":"e","bold":true,"color":"red"}
+        ]}"#).expect("valid json");
+        assert_eq!(json, expected);
+        assert_eq!(compact.to_traditional(), chat.to_traditional());
+
+        let mut same = Chat::from_text("x");
+        same.base_mut().extra.push(Chat::from_text("y").boxed());
+        same.base_mut().extra.push(Chat::from_text("z").boxed());
+        assert_eq!(same.compact(), Chat::from_text("xyz"));
+    }
+
+    fn assert_legacy(source: &str, prefix: char, json: &str) {
+        let expected: Value = serde_json::from_str(json).expect("valid json");
+        let actual = serde_json::to_value(Chat::from_legacy(source, prefix)).expect("should serialize");
+        assert_eq!(actual, expected, "parsing {}", source);
+    }
+
+    #[test]
+    fn test_legacy_color_resets_formatting() {
+        assert_legacy("&l&cred", '&', r#"{"text":"red","color":"red"}"#);
+        assert_legacy("&c&lbold red&9blue", '&', r#"{"text":"","extra":[
+            {"text":"bold red","color":"red","bold":true},{"text":"blue","color":"blue"}]}"#);
+        assert_legacy("&lbold &nboth &oall", '&', r#"{"text":"","extra":[
+            {"text":"bold ","bold":true},
+            {"text":"both ","bold":true,"underlined":true},
+            {"text":"all","bold":true,"underlined":true,"italic":true}]}"#);
+        assert_legacy("&#FF8800orange &lbold&aplain", '&', r##"{"text":"","extra":[
+            {"text":"orange ","color":"#FF8800"},
+            {"text":"bold","color":"#FF8800","bold":true},
+            {"text":"plain","color":"green"}]}"##);
+    }
+
+    #[test]
+    fn test_legacy_reset() {
+        assert_legacy("&c&lx&ry", '&', r#"{"text":"","extra":[{"text":"x","color":"red","bold":true},{"text":"y"}]}"#);
+        assert_legacy("&rstart", '&', r#"{"text":"start"}"#);
+        assert_legacy("&c&r&lx", '&', r#"{"text":"x","bold":true}"#);
+        assert_legacy("&ca&r&rb&r", '&', r#"{"text":"","extra":[{"text":"a","color":"red"},{"text":"b"}]}"#);
+    }
+
+    #[test]
+    fn test_legacy_prefixes_and_literals() {
+        assert_legacy("$cred &cnot §lbold", '$', r#"{"text":"","extra":[
+            {"text":"red &cnot ","color":"red"},{"text":"bold","color":"red","bold":true}]}"#);
+        assert_legacy("&Cupper", '&', r#"{"text":"upper","color":"red"}"#);
+        assert_legacy("&zz & a &#GG0000 &x&f&q x&", '&', r#"{"text":"","extra":[
+            {"text":"&zz & a &#GG0000 &x"},{"text":"&q x&","color":"white"}]}"#);
+        assert_legacy("§x§f§f§8§8§0§0bungee &x&0&0&0&0&A&Aamp", '&', r##"{"text":"","extra":[
+            {"text":"bungee ","color":"#FF8800"},{"text":"amp","color":"#0000AA"}]}"##);
+    }
+
+    #[test]
+    fn test_legacy_hex_emission() {
+        let chat = Chat::from_text("")
+            .append(Chat::from_text("hex").color(ChatColor::Rgb(0xFF, 0x88, 0x00)).bold())
+            .append(Chat::from_text(" plain"))
+            .append(Chat::from_text(" red").color(ColorCode::Red));
+
+        assert_eq!(chat.to_legacy('&', LegacyHexFormat::Hash).expect("is text").as_str(), "&#FF8800&lhex&r plain&c red");
+        assert_eq!(chat.to_legacy(SECTION_SYMBOL, LegacyHexFormat::BungeeCord).expect("is text").as_str(),
+            "§x§f§f§8§8§0§0§lhex§r plain§c red");
+        assert_eq!(chat.to_traditional().expect("is text").as_str(), "§6§lhex§r plain§c red");
+
+        for (prefix, hex) in [('&', LegacyHexFormat::Hash), ('&', LegacyHexFormat::BungeeCord), (SECTION_SYMBOL, LegacyHexFormat::Hash)].iter() {
+            let legacy = chat.to_legacy(*prefix, *hex).expect("is text");
+            assert_eq!(Chat::from_legacy(legacy.as_str(), *prefix), chat.compact(), "round trip of {}", legacy);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use super::*;
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum AnsiColors {
+    // the 16 standard colors only, hex colors are shown as the closest legacy color
+    Basic,
+    // hex colors are sent as 24-bit escapes, legacy colors still use the 16 standard colors
+    TrueColor,
+}
+
+impl ColorCode {
+    pub fn ansi_code(&self) -> u8 {
+        match self {
+            ColorCode::Black => 30,
+            ColorCode::DarkBlue => 34,
+            ColorCode::DarkGreen => 32,
+            ColorCode::DarkAqua => 36,
+            ColorCode::DarkRed => 31,
+            ColorCode::DarkPurple => 35,
+            ColorCode::Gold => 33,
+            ColorCode::Gray => 37,
+            ColorCode::DarkGray => 90,
+            ColorCode::Blue => 94,
+            ColorCode::Green => 92,
+            ColorCode::Aqua => 96,
+            ColorCode::Red => 91,
+            ColorCode::LightPurple => 95,
+            ColorCode::Yellow => 93,
+            ColorCode::White => 97,
+        }
+    }
+}
+
+impl Chat {
+    pub fn to_ansi<R: ChatResolver + ?Sized>(&self, resolver: &R, colors: AnsiColors) -> String {
+        let rendered = self.render(resolver);
+        let mut out = String::new();
+        let mut current = ANSI_RESET.to_owned();
+        for_each_styled(&rendered, &InheritedStyle::default(), &mut |text, style| {
+            let escape = ansi_escape(style, colors);
+            if escape != current {
+                out.push_str(escape.as_str());
+                current = escape;
+            }
+            out.push_str(text);
+        });
+
+        if current != ANSI_RESET {
+            out.push_str(ANSI_RESET);
+        }
+
+        out
+    }
+}
+
+fn ansi_escape(style: &InheritedStyle<'_>, colors: AnsiColors) -> String {
+    let mut codes = alloc::vec![String::from("0")];
+    let mut push_flag = |enabled: bool, code: &str| {
+        if enabled {
+            codes.push(code.to_owned());
+        }
+    };
+
+    push_flag(style.bold, "1");
+    push_flag(style.italic, "3");
+    push_flag(style.underlined, "4");
+    push_flag(style.obfuscated, "5");
+    push_flag(style.strikethrough, "9");
+
+    match (style.color, colors) {
+        (Some(ChatColor::Rgb(r, g, b)), AnsiColors::TrueColor) => codes.push(format!("38;2;{};{};{}", r, g, b)),
+        (Some(color), _) => codes.push(color.to_legacy().ansi_code().to_string()),
+        (None, _) => {}
+    }
+
+    if codes.len() == 1 {
+        ANSI_RESET.to_owned()
+    } else {
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi_inherits_style() {
+        let chat = Chat::from_traditional("plain &c&lred bold&r back &9blue", true);
+        let out = chat.to_ansi(&BTreeMap::new(), AnsiColors::Basic);
+        assert_eq!(out.as_str(), "plain \x1b[0;1;91mred bold\x1b[0m back \x1b[0;94mblue\x1b[0m");
+
+        let mut parent = Chat::from_text("a");
+        parent.base_mut().color = Some(ColorCode::Gold.into());
+        parent.base_mut().underlined = Some(true);
+        let mut child = Chat::from_text("b");
+        child.base_mut().italic = Some(true);
+        parent.base_mut().extra.push(child.boxed());
+        parent.base_mut().extra.push(Chat::from_text("c").boxed());
+
+        let out = parent.to_ansi(&BTreeMap::new(), AnsiColors::Basic);
+        assert_eq!(out.as_str(), "\x1b[0;4;33ma\x1b[0;3;4;33mb\x1b[0;4;33mc\x1b[0m");
+    }
+
+    #[test]
+    fn test_ansi_hex_colors() {
+        let mut chat = Chat::from_text("hex");
+        chat.base_mut().color = Some(ChatColor::Rgb(0x12, 0x34, 0xF0));
+        let locale = BTreeMap::new();
+        assert_eq!(chat.to_ansi(&locale, AnsiColors::TrueColor).as_str(), "\x1b[0;38;2;18;52;240mhex\x1b[0m");
+        assert_eq!(chat.to_ansi(&locale, AnsiColors::Basic).as_str(), "\x1b[0;94mhex\x1b[0m");
+    }
+}
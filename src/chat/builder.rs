@@ -0,0 +1,100 @@
+use super::*;
+
+// fluent construction, such as Chat::from_text("hi").color(ColorCode::Gold).bold().append(Chat::from_text("!"))
+impl Chat {
+    pub fn translate<S: Into<String>>(key: S, with: Vec<Chat>) -> Chat {
+        Chat::Translation(TranslationComponent {
+            translate: key.into(),
+            with: with.into_iter().map(Chat::boxed).collect(),
+            base: BaseComponent::default(),
+        })
+    }
+
+    pub fn keybind<S: Into<String>>(keybind: S) -> Chat {
+        Chat::Keybind(KeybindComponent {
+            keybind: keybind.into(),
+            base: BaseComponent::default(),
+        })
+    }
+
+    pub fn color<C: Into<ChatColor>>(mut self, color: C) -> Self {
+        self.base_mut().color = Some(color.into());
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.base_mut().bold = Some(true);
+        self
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.base_mut().italic = Some(true);
+        self
+    }
+
+    pub fn underlined(mut self) -> Self {
+        self.base_mut().underlined = Some(true);
+        self
+    }
+
+    pub fn strikethrough(mut self) -> Self {
+        self.base_mut().strikethrough = Some(true);
+        self
+    }
+
+    pub fn obfuscated(mut self) -> Self {
+        self.base_mut().obfuscated = Some(true);
+        self
+    }
+
+    pub fn font<S: Into<String>>(mut self, font: S) -> Self {
+        self.base_mut().font = Some(font.into());
+        self
+    }
+
+    pub fn insertion<S: Into<String>>(mut self, insertion: S) -> Self {
+        self.base_mut().insertion = Some(insertion.into());
+        self
+    }
+
+    pub fn click(mut self, event: ChatClickEvent) -> Self {
+        self.base_mut().click_event = Some(event);
+        self
+    }
+
+    pub fn hover(mut self, event: ChatHoverEvent) -> Self {
+        self.base_mut().hover_event = Some(event);
+        self
+    }
+
+    pub fn hover_text(self, text: Chat) -> Self {
+        self.hover(ChatHoverEvent::ShowText(text.boxed()))
+    }
+
+    pub fn append(mut self, child: Chat) -> Self {
+        self.base_mut().extra.push(child.boxed());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder() {
+        let built = Chat::from_text("Hi ")
+            .color(ColorCode::Gold)
+            .bold()
+            .click(ChatClickEvent::RunCommand("/spawn".to_owned()))
+            .append(Chat::from_text("there").italic().hover_text(Chat::from_text("tooltip")))
+            .append(Chat::translate("chat.type.text", alloc::vec![Chat::from_text("a"), Chat::keybind("key.jump")]));
+
+        let expected: Chat = serde_json::from_str(r#"{"text":"Hi ","color":"gold","bold":true,
+            "clickEvent":{"action":"run_command","value":"/spawn"},"extra":[
+                {"text":"there","italic":true,"hoverEvent":{"action":"show_text","contents":"tooltip"}},
+                {"translate":"chat.type.text","with":["a",{"keybind":"key.jump"}]}
+            ]}"#).expect("valid chat");
+        assert_eq!(built, expected);
+    }
+}
@@ -0,0 +1,161 @@
+use super::*;
+
+impl Chat {
+    // every styled run becomes a span with inline styles, events are kept as data- attributes (and links as <a>)
+    pub fn to_html<R: ChatResolver + ?Sized>(&self, resolver: &R) -> String {
+        let rendered = self.render(resolver);
+        let mut out = String::new();
+        for_each_styled(&rendered, &InheritedStyle::default(), &mut |text, style| {
+            let attributes = html_attributes(style, resolver);
+            let link = match style.click_event {
+                Some(ChatClickEvent::OpenUrl(url)) if is_web_url(url) => Some(url),
+                _ => None,
+            };
+
+            match (link, attributes.is_empty()) {
+                (Some(url), _) => {
+                    out.push_str("<a href=\"");
+                    escape_html(url, &mut out);
+                    out.push('"');
+                    out.push_str(attributes.as_str());
+                    out.push('>');
+                    escape_html(text, &mut out);
+                    out.push_str("</a>");
+                }
+                (None, false) => {
+                    out.push_str("<span");
+                    out.push_str(attributes.as_str());
+                    out.push('>');
+                    escape_html(text, &mut out);
+                    out.push_str("</span>");
+                }
+                (None, true) => escape_html(text, &mut out),
+            }
+        });
+
+        out
+    }
+}
+
+fn html_attributes<R: ChatResolver + ?Sized>(style: &InheritedStyle<'_>, resolver: &R) -> String {
+    let mut css = Vec::new();
+    if let Some(color) = style.color {
+        let (r, g, b) = color.rgb();
+        css.push(format!("color:#{:02x}{:02x}{:02x}", r, g, b));
+    }
+    if style.bold {
+        css.push("font-weight:bold".to_owned());
+    }
+    if style.italic {
+        css.push("font-style:italic".to_owned());
+    }
+    match (style.underlined, style.strikethrough) {
+        (true, true) => css.push("text-decoration:underline line-through".to_owned()),
+        (true, false) => css.push("text-decoration:underline".to_owned()),
+        (false, true) => css.push("text-decoration:line-through".to_owned()),
+        (false, false) => {}
+    }
+
+    let mut out = String::new();
+    let mut push_attribute = |name: &str, value: &str| {
+        out.push(' ');
+        out.push_str(name);
+        out.push_str("=\"");
+        escape_html(value, &mut out);
+        out.push('"');
+    };
+
+    if !css.is_empty() {
+        push_attribute("style", css.join(";").as_str());
+    }
+    if style.obfuscated {
+        push_attribute("data-obfuscated", "true");
+    }
+    if let Some(font) = style.font {
+        push_attribute("data-font", font);
+    }
+    if let Some(insertion) = style.insertion {
+        push_attribute("data-insertion", insertion);
+    }
+
+    if let Some(click) = style.click_event {
+        use ChatClickEvent::*;
+
+        let (action, value) = match click {
+            OpenUrl(url) => ("open_url", url.clone()),
+            RunCommand(cmd) => ("run_command", cmd.clone()),
+            SuggestCommand(cmd) => ("suggest_command", cmd.clone()),
+            ChangePage(page) => ("change_page", page.to_string()),
+        };
+        push_attribute("data-click-action", action);
+        push_attribute("data-click-value", value.as_str());
+    }
+
+    if let Some(hover) = style.hover_event {
+        use ChatHoverEvent::*;
+
+        let title = match hover {
+            ShowText(text) => text.to_plain_text(resolver),
+            ShowItem(item) => item.id.clone(),
+            ShowEntity(entity) => match &entity.name {
+                Some(name) => name.to_plain_text(resolver),
+                None => entity.kind.clone(),
+            },
+        };
+        push_attribute("data-hover-action", hover.action());
+        push_attribute("title", title.as_str());
+    }
+
+    out
+}
+
+// only these are safe to put in an href, anything else (javascript: and friends) stays a data- attribute
+fn is_web_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+fn escape_html(text: &str, to: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => to.push_str("&amp;"),
+            '<' => to.push_str("&lt;"),
+            '>' => to.push_str("&gt;"),
+            '"' => to.push_str("&quot;"),
+            '\'' => to.push_str("&#39;"),
+            other => to.push(other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_styles() {
+        let chat = Chat::from_traditional("<b> &c&lred &r&n&mboth", true);
+        let out = chat.to_html(&BTreeMap::new());
+        assert_eq!(out.as_str(), "&lt;b&gt; <span style=\"color:#ff5555;font-weight:bold\">red </span>\
+            <span style=\"text-decoration:underline line-through\">both</span>");
+    }
+
+    #[test]
+    fn test_html_events() {
+        let mut chat = Chat::from_text("click ");
+        chat.base_mut().click_event = Some(ChatClickEvent::OpenUrl("https://example.com/?a=1&b=2".to_owned()));
+        chat.base_mut().hover_event = Some(ChatHoverEvent::ShowText(Chat::from_text("go \"there\"").boxed()));
+        chat.base_mut().extra.push(Chat::from_text("me").boxed());
+
+        let mut evil = Chat::from_text("x");
+        evil.base_mut().click_event = Some(ChatClickEvent::OpenUrl("javascript:alert(1)".to_owned()));
+        evil.base_mut().insertion = Some("/msg".to_owned());
+        chat.base_mut().extra.push(evil.boxed());
+
+        let out = chat.to_html(&BTreeMap::new());
+        let link = "<a href=\"https://example.com/?a=1&amp;b=2\" data-click-action=\"open_url\" \
+            data-click-value=\"https://example.com/?a=1&amp;b=2\" data-hover-action=\"show_text\" title=\"go &quot;there&quot;\">";
+        assert_eq!(out, format!("{}click </a>{}me</a><span data-insertion=\"/msg\" data-click-action=\"open_url\" \
+            data-click-value=\"javascript:alert(1)\" data-hover-action=\"show_text\" title=\"go &quot;there&quot;\">x</span>", link, link));
+    }
+}
@@ -0,0 +1,295 @@
+use super::*;
+
+impl Chat {
+    // parses tags such as <gold><bold>Hi</bold> <click:run_command:/spawn>here</click>, where anything that is not a
+    // known tag (or a tag closing something that isn't open) is kept as text, and \< is a literal <
+    pub fn from_markup(source: &str) -> Chat {
+        MarkupParser::new(source).parse()
+    }
+}
+
+struct MarkupParser {
+    source: Vec<char>,
+    at: usize,
+
+    // state
+    text: String,
+    // every open tag, with the style inside of it
+    open: Vec<(&'static str, BaseComponent)>,
+
+    // all the parts we've already seen
+    done: Vec<BoxedChat>,
+}
+
+impl MarkupParser {
+
+    fn new(source: &str) -> Self {
+        Self {
+            source: source.chars().collect(),
+            at: 0,
+
+            text: String::new(),
+            open: Vec::new(),
+
+            done: Vec::new(),
+        }
+    }
+
+    fn parse(mut self) -> Chat {
+        while let Some(next) = self.consume_char() {
+            match next {
+                '\\' => match self.source.get(self.at) {
+                    Some(escaped) if *escaped == '<' || *escaped == '\\' => {
+                        self.text.push(*escaped);
+                        self.at += 1;
+                    }
+                    _ => self.text.push('\\'),
+                },
+                '<' => if !self.consume_tag() {
+                    self.text.push('<');
+                },
+                other => self.text.push(other),
+            }
+        }
+
+        self.finish_current();
+        let mut out = Chat::from_text("");
+        out.base_mut().extra = self.done;
+        out.compact()
+    }
+
+    fn consume_char(&mut self) -> Option<char> {
+        let out = self.source.get(self.at).copied();
+        if out.is_some() {
+            self.at += 1;
+        }
+        out
+    }
+
+    // called just after a <, and only moves past the tag if it was understood
+    fn consume_tag(&mut self) -> bool {
+        let end = match self.find_tag_end() {
+            Some(end) => end,
+            None => return false,
+        };
+
+        let raw: String = self.source[self.at..end].iter().collect();
+        let handled = if let Some(closing) = raw.strip_prefix('/') {
+            self.close_tag(closing)
+        } else {
+            self.open_tag(split_args(raw.as_str()))
+        };
+
+        if handled {
+            self.at = end + 1;
+        }
+        handled
+    }
+
+    fn find_tag_end(&self) -> Option<usize> {
+        let mut quote: Option<char> = None;
+        for idx in self.at..self.source.len() {
+            let c = self.source[idx];
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => {}
+                None if c == '\'' || c == '"' => quote = Some(c),
+                None if c == '>' => return Some(idx),
+                None if c == '<' => return None,
+                None => {}
+            }
+        }
+
+        None
+    }
+
+    fn close_tag(&mut self, name: &str) -> bool {
+        let name = match tag_name(name.to_ascii_lowercase().as_str()) {
+            Some(name) => name,
+            None => return false,
+        };
+
+        match self.open.iter().rposition(move |(open, _)| *open == name) {
+            Some(idx) => {
+                self.finish_current();
+                self.open.truncate(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn open_tag(&mut self, args: Vec<String>) -> bool {
+        let raw_name = args[0].to_ascii_lowercase();
+        let name = match tag_name(raw_name.as_str()) {
+            Some(name) => name,
+            None => return false,
+        };
+
+        if name == "reset" {
+            self.finish_current();
+            self.open.clear();
+            return true;
+        }
+
+        let rest = args[1..].join(":");
+        let mut style = self.current_style();
+        let applied = match name {
+            "color" => {
+                let color = if raw_name == "color" || raw_name == "c" { rest.as_str() } else { raw_name.as_str() };
+                style.color = ChatColor::from_name(color);
+                style.color.is_some()
+            }
+            "bold" => set_flag(&mut style.bold),
+            "italic" => set_flag(&mut style.italic),
+            "underlined" => set_flag(&mut style.underlined),
+            "strikethrough" => set_flag(&mut style.strikethrough),
+            "obfuscated" => set_flag(&mut style.obfuscated),
+            "font" | "insertion" if !rest.is_empty() => {
+                if name == "font" {
+                    style.font = Some(rest);
+                } else {
+                    style.insertion = Some(rest);
+                }
+                true
+            }
+            "click" => {
+                style.click_event = args.get(1).and_then(|action| click_event(action.as_str(), args[2..].join(":")));
+                style.click_event.is_some()
+            }
+            "hover" if args.get(1).map(String::as_str) == Some("show_text") => {
+                let text = Chat::from_markup(args[2..].join(":").as_str());
+                style.hover_event = Some(ChatHoverEvent::ShowText(text.boxed()));
+                true
+            }
+            _ => false,
+        };
+
+        if applied {
+            self.finish_current();
+            self.open.push((name, style));
+            true
+        } else {
+            false
+        }
+    }
+
+    fn current_style(&self) -> BaseComponent {
+        self.open.last().map(move |(_, style)| style.clone_style()).unwrap_or_default()
+    }
+
+    fn finish_current(&mut self) {
+        if !self.text.is_empty() {
+            let current = Chat::Text(TextComponent {
+                text: core::mem::take(&mut self.text),
+                base: self.current_style(),
+            });
+            self.done.push(current.boxed());
+        }
+    }
+}
+
+fn set_flag(flag: &mut Option<bool>) -> bool {
+    *flag = Some(true);
+    true
+}
+
+fn tag_name(name: &str) -> Option<&'static str> {
+    match name {
+        "b" | "bold" => Some("bold"),
+        "i" | "em" | "italic" => Some("italic"),
+        "u" | "underline" | "underlined" => Some("underlined"),
+        "st" | "strikethrough" => Some("strikethrough"),
+        "obf" | "obfuscated" => Some("obfuscated"),
+        "c" | "color" => Some("color"),
+        "font" => Some("font"),
+        "insert" | "insertion" => Some("insertion"),
+        "click" => Some("click"),
+        "hover" => Some("hover"),
+        "reset" => Some("reset"),
+        other if ChatColor::from_name(other).is_some() => Some("color"),
+        _ => None,
+    }
+}
+
+fn click_event(action: &str, value: String) -> Option<ChatClickEvent> {
+    use ChatClickEvent::*;
+
+    match action {
+        "open_url" => Some(OpenUrl(value)),
+        "run_command" => Some(RunCommand(value)),
+        "suggest_command" => Some(SuggestCommand(value)),
+        "change_page" => value.parse().ok().map(ChangePage),
+        _ => None,
+    }
+}
+
+// splits on : outside of quotes, dropping the quotes themselves
+fn split_args(raw: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    for c in raw.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == ':' => out.push(core::mem::take(&mut current)),
+            None => current.push(c),
+        }
+    }
+
+    out.push(current);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_markup(markup: &str, json: &str) {
+        let expected: Chat = serde_json::from_str(json).expect("valid chat");
+        assert_eq!(Chat::from_markup(markup), expected, "parsing {}", markup);
+    }
+
+    #[test]
+    fn test_markup_styles() {
+        assert_markup("<gold><bold>Hi</bold> <click:run_command:/spawn>here</click>",
+            r#"{"text":"","extra":[
+                {"text":"Hi","color":"gold","bold":true},
+                {"text":" ","color":"gold"},
+                {"text":"here","color":"gold","clickEvent":{"action":"run_command","value":"/spawn"}}
+            ]}"#);
+
+        assert_markup("<#00ff00>g</#00ff00><color:#123456>h</c> <u><st>x</u>y",
+            r##"{"text":"","extra":[
+                {"text":"g","color":"#00FF00"},
+                {"text":"h","color":"#123456"},
+                {"text":" "},
+                {"text":"x","underlined":true,"strikethrough":true},
+                {"text":"y"}
+            ]}"##);
+
+        assert_markup("plain", r#"{"text":"plain"}"#);
+        assert_markup("<red>a<reset>b", r#"{"text":"","extra":[{"text":"a","color":"red"},{"text":"b"}]}"#);
+    }
+
+    #[test]
+    fn test_markup_events() {
+        assert_markup("<click:open_url:https://example.com/a>link</click> <click:suggest_command:'/tell a:b <x>'>s",
+            r#"{"text":"","extra":[
+                {"text":"link","clickEvent":{"action":"open_url","value":"https://example.com/a"}},
+                {"text":" "},
+                {"text":"s","clickEvent":{"action":"suggest_command","value":"/tell a:b <x>"}}
+            ]}"#);
+
+        assert_markup("<hover:show_text:'<red>careful'><insert:hello>x",
+            r#"{"text":"x","insertion":"hello","hoverEvent":{"action":"show_text","contents":{"text":"careful","color":"red"}}}"#);
+    }
+
+    #[test]
+    fn test_markup_literals() {
+        assert_markup("a <notatag> b \\<gold> </gold> 1 < 2 \\o/ <click:nope:x>",
+            r#"{"text":"a <notatag> b <gold> </gold> 1 < 2 \\o/ <click:nope:x>"}"#);
+    }
+}
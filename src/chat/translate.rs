@@ -0,0 +1,241 @@
+use super::*;
+
+// supplies what the client would normally know locally: the language file, key bindings and scores
+pub trait ChatResolver {
+    fn translation(&self, key: &str) -> Option<&str>;
+
+    fn keybind(&self, _keybind: &str) -> Option<String> {
+        None
+    }
+
+    fn score(&self, score: &ScoreComponentObjective) -> Option<String> {
+        score.value.clone()
+    }
+}
+
+impl ChatResolver for BTreeMap<String, String> {
+    fn translation(&self, key: &str) -> Option<&str> {
+        self.get(key).map(String::as_str)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: std::hash::BuildHasher> ChatResolver for std::collections::HashMap<String, String, S> {
+    fn translation(&self, key: &str) -> Option<&str> {
+        self.get(key).map(String::as_str)
+    }
+}
+
+impl Chat {
+    // turns every component into a text component, keeping styles, so that the result can be displayed or logged
+    pub fn render<R: ChatResolver + ?Sized>(&self, resolver: &R) -> Chat {
+        use Chat::*;
+
+        let mut base = self.base().clone_style();
+        let text = match self {
+            Text(body) => body.text.clone(),
+            Translation(body) => {
+                base.extra.extend(translate(body, resolver));
+                String::new()
+            }
+            Keybind(body) => resolver.keybind(body.keybind.as_str())
+                .or_else(|| resolver.translation(body.keybind.as_str()).map(str::to_owned))
+                .unwrap_or_else(|| body.keybind.clone()),
+            Score(body) => resolver.score(&body.score).unwrap_or_default(),
+            Selector(body) => body.selector.clone(),
+            Nbt(_) => String::new(),
+        };
+
+        base.extra.extend(self.siblings().iter().map(move |child| child.render(resolver).boxed()));
+        Chat::Text(TextComponent { text, base })
+    }
+
+    pub fn to_plain_text<R: ChatResolver + ?Sized>(&self, resolver: &R) -> String {
+        let mut out = String::new();
+        self.render(resolver).collect_text(&mut out);
+        out
+    }
+}
+
+fn translate<R: ChatResolver + ?Sized>(body: &TranslationComponent, resolver: &R) -> Vec<BoxedChat> {
+    // vanilla shows the key itself when it has no translation for it
+    let format = resolver.translation(body.translate.as_str()).unwrap_or(body.translate.as_str());
+    match split_format(format, body.with.len()) {
+        Some(parts) => parts.into_iter().map(move |part| match part {
+            FormatPart::Literal(text) => Chat::from_text(text.as_str()).boxed(),
+            FormatPart::Arg(idx) => body.with[idx].render(resolver).boxed(),
+        }).collect(),
+        None => alloc::vec![Chat::from_text(format).boxed()],
+    }
+}
+
+enum FormatPart {
+    Literal(String),
+    Arg(usize),
+}
+
+// splits on %s, %1$s and %%, returning None (like vanilla's TranslatableFormatException) for any other
+// conversion or a missing argument
+fn split_format(format: &str, arg_count: usize) -> Option<Vec<FormatPart>> {
+    let chars: Vec<char> = format.chars().collect();
+    let mut out = Vec::new();
+    let mut literal = String::new();
+    let mut next_arg = 0;
+    let mut at = 0;
+    while at < chars.len() {
+        let c = chars[at];
+        at += 1;
+        if c != '%' {
+            literal.push(c);
+            continue;
+        }
+
+        let digits_end = (at..chars.len()).find(|idx| !chars[*idx].is_ascii_digit()).unwrap_or(chars.len());
+        let (position, conversion_at) = if digits_end > at && chars.get(digits_end) == Some(&'$') {
+            let position: String = chars[at..digits_end].iter().collect();
+            (Some(position.parse::<usize>().ok()?), digits_end + 1)
+        } else {
+            (None, at)
+        };
+
+        match chars.get(conversion_at) {
+            Some('%') if position.is_none() => literal.push('%'),
+            Some('s') => {
+                let idx = match position {
+                    Some(position) => position.checked_sub(1)?,
+                    None => {
+                        next_arg += 1;
+                        next_arg - 1
+                    }
+                };
+
+                if idx >= arg_count {
+                    return None;
+                }
+
+                if !literal.is_empty() {
+                    out.push(FormatPart::Literal(core::mem::take(&mut literal)));
+                }
+                out.push(FormatPart::Arg(idx));
+            }
+            Some(other) if !other.is_ascii_alphabetic() => {
+                // not a format specifier at all, so the % is just text
+                literal.push('%');
+                continue;
+            }
+            _ => return None,
+        }
+
+        at = conversion_at + 1;
+    }
+
+    if !literal.is_empty() {
+        out.push(FormatPart::Literal(literal));
+    }
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn locale() -> BTreeMap<String, String> {
+        let mut out = BTreeMap::new();
+        for (key, value) in [
+            ("chat.type.text", "<%s> %s"),
+            ("death.attack.fall", "%1$s hit the ground too hard"),
+            ("death.attack.player", "%1$s was slain by %2$s"),
+            ("swapped", "%2$s then %1$s (100%% sure, 50% maybe)"),
+            ("broken", "%d things"),
+            ("key.jump", "Jump"),
+        ].iter() {
+            out.insert((*key).to_owned(), (*value).to_owned());
+        }
+        out
+    }
+
+    fn translation(key: &str, with: Vec<Chat>) -> Chat {
+        Chat::Translation(TranslationComponent {
+            translate: key.to_owned(),
+            with: with.into_iter().map(Chat::boxed).collect(),
+            base: BaseComponent::default(),
+        })
+    }
+
+    #[test]
+    fn test_translate_placeholders() {
+        let locale = locale();
+        let chat = translation("chat.type.text", vec![Chat::from_text("Steve"), Chat::from_text("hello")]);
+        assert_eq!(chat.to_plain_text(&locale).as_str(), "<Steve> hello");
+
+        let chat = translation("death.attack.player", vec![
+            Chat::from_text("Steve"),
+            translation("death.attack.fall", vec![Chat::from_text("Alex")]),
+        ]);
+        assert_eq!(chat.to_plain_text(&locale).as_str(), "Steve was slain by Alex hit the ground too hard");
+
+        let chat = translation("swapped", vec![Chat::from_text("a"), Chat::from_text("b")]);
+        assert_eq!(chat.to_plain_text(&locale).as_str(), "b then a (100% sure, 50% maybe)");
+    }
+
+    #[test]
+    fn test_translate_fallbacks() {
+        let locale = locale();
+        assert_eq!(translation("missing.key", vec![]).to_plain_text(&locale).as_str(), "missing.key");
+        assert_eq!(translation("broken", vec![Chat::from_text("3")]).to_plain_text(&locale).as_str(), "%d things");
+        assert_eq!(translation("death.attack.fall", vec![]).to_plain_text(&locale).as_str(), "%1$s hit the ground too hard");
+    }
+
+    #[test]
+    fn test_render_keeps_style() {
+        let mut chat = translation("death.attack.fall", vec![Chat::from_text("Steve")]);
+        chat.base_mut().color = Some(ColorCode::Red.into());
+        chat.base_mut().extra.push(Chat::from_text("!").boxed());
+
+        let rendered = chat.render(&locale());
+        assert_eq!(rendered.base().color, Some(ColorCode::Red.into()));
+        let texts: Vec<String> = rendered.siblings().iter().map(move |child| child.to_plain_text(&locale())).collect();
+        assert_eq!(texts, vec!["Steve", " hit the ground too hard", "!"]);
+    }
+
+    struct Resolver(BTreeMap<String, String>);
+
+    impl ChatResolver for Resolver {
+        fn translation(&self, key: &str) -> Option<&str> {
+            self.0.translation(key)
+        }
+
+        fn keybind(&self, keybind: &str) -> Option<String> {
+            if keybind == "key.sneak" {
+                Some("Left Shift".to_owned())
+            } else {
+                None
+            }
+        }
+
+        fn score(&self, score: &ScoreComponentObjective) -> Option<String> {
+            Some(format!("{}@{}", score.name, score.objective.as_deref().unwrap_or("?")))
+        }
+    }
+
+    #[test]
+    fn test_keybind_and_score_lookups() {
+        let keybind = move |key: &str| Chat::Keybind(KeybindComponent { keybind: key.to_owned(), base: BaseComponent::default() });
+        let score = Chat::Score(ScoreComponent {
+            score: ScoreComponentObjective { name: "Steve".to_owned(), objective: Some("kills".to_owned()), value: Some("7".to_owned()) },
+            base: BaseComponent::default(),
+        });
+
+        let locale = locale();
+        assert_eq!(keybind("key.sneak").to_plain_text(&locale).as_str(), "key.sneak");
+        assert_eq!(keybind("key.jump").to_plain_text(&locale).as_str(), "Jump");
+        assert_eq!(score.to_plain_text(&locale).as_str(), "7");
+
+        let resolver = Resolver(locale);
+        assert_eq!(keybind("key.sneak").to_plain_text(&resolver).as_str(), "Left Shift");
+        assert_eq!(keybind("key.jump").to_plain_text(&resolver).as_str(), "Jump");
+        assert_eq!(score.to_plain_text(&resolver).as_str(), "Steve@kills");
+    }
+}
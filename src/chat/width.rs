@@ -0,0 +1,208 @@
+use super::*;
+
+// how far the default font (ascii.png) moves right after drawing c, including the 1px gap, which bold widens by 1px.
+// anything outside of ascii is drawn by other fonts, and is assumed to be as wide as most letters
+pub fn char_width(c: char, bold: bool) -> u32 {
+    let advance = match c {
+        '!' | '\'' | ',' | '.' | ':' | ';' | 'i' | '|' => 2,
+        '`' | 'l' => 3,
+        ' ' | '"' | 'I' | '[' | ']' | 't' => 4,
+        '(' | ')' | '*' | '<' | '>' | 'f' | 'k' | '{' | '}' => 5,
+        '@' | '~' => 7,
+        _ => 6,
+    };
+
+    if bold {
+        advance + 1
+    } else {
+        advance
+    }
+}
+
+impl Chat {
+    // the width in pixels of the widest line
+    pub fn width<R: ChatResolver + ?Sized>(&self, resolver: &R) -> u32 {
+        let mut widest = 0;
+        let mut current = 0;
+        for (c, bold) in styled_chars(&self.render(resolver).flatten()) {
+            if c == '\n' {
+                current = 0;
+            } else {
+                current += char_width(c, bold);
+                widest = widest.max(current);
+            }
+        }
+
+        widest
+    }
+
+    // splits into lines no wider than max_width, breaking at spaces (which are dropped) and newlines, and only
+    // breaking words when a single word doesn't fit on a line by itself
+    pub fn wrap<R: ChatResolver + ?Sized>(&self, resolver: &R, max_width: u32) -> Vec<Chat> {
+        let parts = self.render(resolver).flatten();
+        let mut wrapper = Wrapper { max_width, lines: Vec::new(), line: Vec::new(), line_width: 0, word: Vec::new(), word_width: 0 };
+        for (idx, part) in parts.iter().enumerate() {
+            let (text, bold) = match part {
+                Chat::Text(body) => (body.text.as_str(), body.base.bold == Some(true)),
+                _ => continue,
+            };
+
+            for c in text.chars() {
+                match c {
+                    '\n' => {
+                        wrapper.finish_word();
+                        wrapper.finish_line();
+                    }
+                    ' ' => {
+                        wrapper.finish_word();
+                        wrapper.push_space(idx, char_width(c, bold));
+                    }
+                    other => {
+                        let width = char_width(other, bold);
+                        wrapper.word.push((other, idx, width));
+                        wrapper.word_width += width;
+                    }
+                }
+            }
+        }
+
+        wrapper.finish_word();
+        wrapper.finish_line();
+        wrapper.lines.into_iter().map(move |line| line_to_chat(&parts, line)).collect()
+    }
+}
+
+fn styled_chars(parts: &[Chat]) -> impl Iterator<Item=(char, bool)> + '_ {
+    parts.iter().filter_map(move |part| match part {
+        Chat::Text(body) => Some(body.text.chars().map(move |c| (c, body.base.bold == Some(true)))),
+        _ => None,
+    }).flatten()
+}
+
+// chars are stored with the index of the flattened part they came from, so that their style can be restored
+struct Wrapper {
+    max_width: u32,
+    lines: Vec<Vec<(char, usize)>>,
+    line: Vec<(char, usize)>,
+    line_width: u32,
+    word: Vec<(char, usize, u32)>,
+    word_width: u32,
+}
+
+impl Wrapper {
+    fn push_space(&mut self, idx: usize, width: u32) {
+        if self.line_width + width > self.max_width {
+            self.finish_line();
+        } else {
+            self.line.push((' ', idx));
+            self.line_width += width;
+        }
+    }
+
+    fn finish_word(&mut self) {
+        if self.word.is_empty() {
+            return;
+        }
+
+        if self.line_width + self.word_width > self.max_width && !self.line.is_empty() {
+            self.finish_line();
+        }
+
+        if self.word_width > self.max_width {
+            self.split_word();
+        }
+
+        self.line.extend(self.word.drain(..).map(move |(c, idx, _)| (c, idx)));
+        self.line_width += self.word_width;
+        self.word_width = 0;
+    }
+
+    // the word is too long for any line, so it fills as many lines as it needs
+    fn split_word(&mut self) {
+        let word = core::mem::take(&mut self.word);
+        let mut width = 0;
+        for (c, idx, c_width) in word {
+            if width + c_width > self.max_width && !self.word.is_empty() {
+                self.line.extend(self.word.drain(..).map(move |(c, idx, _)| (c, idx)));
+                self.finish_line();
+                width = 0;
+            }
+
+            self.word.push((c, idx, c_width));
+            width += c_width;
+        }
+
+        self.word_width = width;
+    }
+
+    fn finish_line(&mut self) {
+        while self.line.last().map(move |(c, _)| *c == ' ').unwrap_or(false) {
+            self.line.pop();
+        }
+
+        self.lines.push(core::mem::take(&mut self.line));
+        self.line_width = 0;
+    }
+}
+
+fn line_to_chat(parts: &[Chat], line: Vec<(char, usize)>) -> Chat {
+    let mut out = Chat::from_text("");
+    let mut run: Option<(String, usize)> = None;
+    for (c, idx) in line {
+        match &mut run {
+            Some((text, run_idx)) if *run_idx == idx => text.push(c),
+            _ => {
+                if let Some((text, run_idx)) = run.take() {
+                    out.base_mut().extra.push(styled_text(&parts[run_idx], text).boxed());
+                }
+                let mut text = String::new();
+                text.push(c);
+                run = Some((text, idx));
+            }
+        }
+    }
+
+    if let Some((text, run_idx)) = run {
+        out.base_mut().extra.push(styled_text(&parts[run_idx], text).boxed());
+    }
+
+    out.compact()
+}
+
+fn styled_text(part: &Chat, text: String) -> Chat {
+    Chat::Text(TextComponent { text, base: part.base().clone_style() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_width() {
+        let locale = BTreeMap::new();
+        assert_eq!(char_width('i', false), 2);
+        assert_eq!(char_width('i', true), 3);
+        assert_eq!(Chat::from_text("Hello").width(&locale), 24);
+        assert_eq!(Chat::from_text("Hel").append(Chat::from_text("lo").bold()).width(&locale), 26);
+        assert_eq!(Chat::from_text("ab\nlonger").width(&locale), 33);
+
+        let mut names = BTreeMap::new();
+        names.insert("greeting".to_owned(), "Hi %s".to_owned());
+        assert_eq!(Chat::translate("greeting", alloc::vec![Chat::from_text("all")]).width(&names), 24);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let locale = BTreeMap::new();
+        let chat = Chat::from_text("aaa ").color(ColorCode::Red).append(Chat::from_text("bbb ccc").bold());
+        let lines = chat.wrap(&locale, 50);
+        assert_eq!(lines, alloc::vec![
+            Chat::from_text("").append(Chat::from_text("aaa ").color(ColorCode::Red)).append(Chat::from_text("bbb").color(ColorCode::Red).bold()),
+            Chat::from_text("ccc").color(ColorCode::Red).bold(),
+        ]);
+        assert!(lines.iter().all(|line| line.width(&locale) <= 50));
+
+        let lines = Chat::from_text("abcdefgh\nx").wrap(&locale, 18);
+        assert_eq!(lines, alloc::vec![Chat::from_text("abc"), Chat::from_text("def"), Chat::from_text("gh"), Chat::from_text("x")]);
+    }
+}
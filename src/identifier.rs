@@ -0,0 +1,282 @@
+use crate::{Deserialize, DeserializeErr, DeserializeResult, Serialize, SerializeResult, Serializer};
+use alloc::{string::{String, ToString}, fmt, format};
+
+#[cfg(all(test, feature = "std"))]
+use crate::protocol::TestRandom;
+
+pub const DEFAULT_NAMESPACE: &str = "minecraft";
+
+// before 1.13 identifiers were just lowercased, since then anything outside of [a-z0-9_.-] (and / in paths) is invalid
+pub const STRICT_IDENTIFIER_PROTOCOL_VERSION: i32 = 393;
+
+// a namespace:path pair (resource location), such as minecraft:stone or bungeecord:main. "stone" and "minecraft:stone"
+// are equal, but each is written back out exactly as it was parsed
+#[derive(Clone)]
+pub struct Identifier {
+    namespace: String,
+    path: String,
+    raw: String,
+}
+
+impl PartialEq for Identifier {
+    fn eq(&self, other: &Self) -> bool {
+        self.namespace == other.namespace && self.path == other.path
+    }
+}
+
+impl Eq for Identifier {}
+
+impl core::hash::Hash for Identifier {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.namespace.hash(state);
+        self.path.hash(state);
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (self.namespace.as_str(), self.path.as_str()).cmp(&(other.namespace.as_str(), other.path.as_str()))
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!("{}:{}", self.namespace, self.path))
+    }
+}
+
+impl fmt::Debug for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!("Identifier{{{}:{}}}", self.namespace, self.path))
+    }
+}
+
+impl Identifier {
+    pub fn new(namespace: &str, path: &str) -> Option<Self> {
+        Self::new_with_raw(namespace, path, format!("{}:{}", namespace, path))
+    }
+
+    fn new_with_raw(namespace: &str, path: &str, raw: String) -> Option<Self> {
+        if namespace.chars().all(is_namespace_char) && path.chars().all(is_path_char) {
+            Some(Self {
+                namespace: namespace.to_string(),
+                path: path.to_string(),
+                raw,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn minecraft(path: &str) -> Option<Self> {
+        Self::new(DEFAULT_NAMESPACE, path)
+    }
+
+    // the namespace is optional, and defaults to minecraft (as does an empty one, like ":stone")
+    pub fn parse(from: &str) -> Option<Self> {
+        let (namespace, path) = split_identifier(from);
+        Self::new_with_raw(namespace, path, from.to_string())
+    }
+
+    pub fn parse_for_version(from: &str, protocol_version: i32) -> Option<Self> {
+        if protocol_version >= STRICT_IDENTIFIER_PROTOCOL_VERSION {
+            return Self::parse(from);
+        }
+
+        let (namespace, path) = split_identifier(from);
+        Some(Self {
+            namespace: namespace.to_lowercase(),
+            path: path.to_lowercase(),
+            raw: from.to_string(),
+        })
+    }
+
+    pub fn namespace(&self) -> &str {
+        self.namespace.as_str()
+    }
+
+    pub fn path(&self) -> &str {
+        self.path.as_str()
+    }
+
+    // the text this was parsed from (or namespace:path when built with new), which is what gets serialized
+    pub fn as_str(&self) -> &str {
+        self.raw.as_str()
+    }
+
+    pub fn is_vanilla(&self) -> bool {
+        self.namespace == DEFAULT_NAMESPACE
+    }
+}
+
+fn split_identifier(from: &str) -> (&str, &str) {
+    match from.find(':') {
+        Some(0) => (DEFAULT_NAMESPACE, &from[1..]),
+        Some(idx) => (&from[..idx], &from[idx + 1..]),
+        None => (DEFAULT_NAMESPACE, from),
+    }
+}
+
+fn is_namespace_char(c: char) -> bool {
+    matches!(c, 'a'..='z' | '0'..='9' | '_' | '.' | '-')
+}
+
+fn is_path_char(c: char) -> bool {
+    is_namespace_char(c) || c == '/'
+}
+
+impl Serialize for Identifier {
+    fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
+        to.serialize_other(&self.raw)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.raw.serialized_size()
+    }
+}
+
+impl Deserialize for Identifier {
+    fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
+        String::mc_deserialize(data)?.try_map(move |raw| {
+            Identifier::parse(raw.as_str())
+                .ok_or_else(move || DeserializeErr::CannotUnderstandValue(format!("invalid identifier {:?}", raw)))
+        })
+    }
+}
+
+impl serde::Serialize for Identifier {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as serde::Serializer>::Ok, <S as serde::Serializer>::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.raw.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Identifier {
+    fn deserialize<D>(deserializer: D) -> Result<Self, <D as serde::Deserializer<'de>>::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct Visitor;
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = Identifier;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(formatter, "a namespace:path identifier")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Identifier::parse(v).ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_str(Visitor {})
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+impl TestRandom for Identifier {
+    fn test_gen_random() -> Self {
+        const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789_.-";
+        let gen_part = |max_len: usize, allow_slash: bool| -> String {
+            let len = 1 + (rand::random::<usize>() % max_len);
+            (0..len).map(move |_| {
+                if allow_slash && rand::random::<u8>() < 26 {
+                    '/'
+                } else {
+                    CHARS[rand::random::<usize>() % CHARS.len()] as char
+                }
+            }).collect()
+        };
+
+        let path = gen_part(32, true);
+        match rand::random::<u8>() % 3 {
+            0 => Self::parse(path.as_str()),
+            1 => Self::minecraft(path.as_str()),
+            _ => Self::new(gen_part(16, false).as_str(), path.as_str()),
+        }.expect("generated a valid identifier")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BytesSerializer;
+
+    #[test]
+    fn test_parse_identifier() {
+        let stone = Identifier::parse("minecraft:stone").expect("valid identifier");
+        assert_eq!((stone.namespace(), stone.path()), ("minecraft", "stone"));
+        assert!(stone.is_vanilla());
+        assert_eq!(Identifier::parse("stone"), Some(stone.clone()));
+        assert_eq!(Identifier::parse(":stone"), Some(stone.clone()));
+        assert_eq!(Identifier::minecraft("stone"), Some(stone));
+
+        let channel = Identifier::parse("bungeecord:main").expect("valid identifier");
+        assert!(!channel.is_vanilla());
+        assert_eq!(channel.to_string().as_str(), "bungeecord:main");
+
+        let nested = Identifier::parse("my_mod:textures/gui/icon-1.png").expect("valid identifier");
+        assert_eq!(nested.path(), "textures/gui/icon-1.png");
+    }
+
+    #[test]
+    fn test_invalid_identifier() {
+        assert_eq!(Identifier::parse("Minecraft:stone"), None);
+        assert_eq!(Identifier::parse("minecraft:Stone"), None);
+        assert_eq!(Identifier::parse("my/mod:stone"), None);
+        assert_eq!(Identifier::parse("a:b:c"), None);
+        assert_eq!(Identifier::parse("minecraft:stone block"), None);
+        assert_eq!(Identifier::new("minecraft", "with:colon"), None);
+    }
+
+    #[test]
+    fn test_legacy_identifier() {
+        let brand = Identifier::parse_for_version("MC|Brand", 340).expect("anything goes before 1.13");
+        assert_eq!((brand.namespace(), brand.path()), ("minecraft", "mc|brand"));
+        let legacy = Identifier::parse_for_version("BungeeCord:Main", 340).expect("anything goes before 1.13");
+        assert_eq!((legacy.namespace(), legacy.path()), ("bungeecord", "main"));
+        assert_eq!(Identifier::parse_for_version("BungeeCord:Main", STRICT_IDENTIFIER_PROTOCOL_VERSION), None);
+    }
+
+    #[test]
+    fn test_identifier_serialize() {
+        let id = Identifier::parse("minecraft:stone").expect("valid identifier");
+        let mut out = BytesSerializer::default();
+        id.mc_serialize(&mut out).expect("serializes");
+        let bytes = out.into_bytes();
+        assert_eq!(bytes.as_slice(), b"\x0fminecraft:stone");
+        assert_eq!(Identifier::mc_deserialize(bytes.as_slice()).expect("deserializes").value, id);
+        assert!(Identifier::mc_deserialize(b"\x05Stone").is_err());
+
+        assert_eq!(serde_json::to_string(&id).expect("serializes").as_str(), "\"minecraft:stone\"");
+        assert_eq!(serde_json::from_str::<Identifier>("\"stone\"").expect("deserializes"), id);
+        assert!(serde_json::from_str::<Identifier>("\"Stone\"").is_err());
+    }
+
+    #[test]
+    fn test_identifier_round_trip_is_lossless() {
+        for raw in ["stone", ":stone", "minecraft:stone", "bungeecord:main"].iter() {
+            let id = Identifier::parse(raw).expect("valid identifier");
+            assert_eq!(id.as_str(), *raw);
+
+            let mut out = BytesSerializer::default();
+            id.mc_serialize(&mut out).expect("serializes");
+            let bytes = out.into_bytes();
+            assert_eq!(bytes.len(), id.serialized_size());
+            assert_eq!(String::mc_deserialize(bytes.as_slice()).expect("deserializes").value.as_str(), *raw);
+            assert_eq!(serde_json::to_string(&id).expect("serializes"), format!("\"{}\"", raw));
+        }
+
+        let legacy = Identifier::parse_for_version("MC|Brand", 340).expect("anything goes before 1.13");
+        assert_eq!(legacy.as_str(), "MC|Brand");
+        assert_eq!(Identifier::parse("stone"), Identifier::parse("minecraft:stone"));
+    }
+}
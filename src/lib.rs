@@ -10,12 +10,14 @@ extern crate test;
 mod deserialize;
 mod serialize;
 pub mod nbt;
+pub mod profile;
 pub mod protocol;
 pub mod status;
 pub mod types;
 pub mod utils;
 pub mod uuid;
 mod chat;
+mod identifier;
 pub mod byte_order;
 
 #[cfg(feature = "v1_15_2")]
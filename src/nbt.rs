@@ -4,7 +4,12 @@ use alloc::{string::{String, ToString}, borrow::ToOwned, fmt, vec::Vec, vec, for
 
 #[cfg(all(test, feature = "std"))]
 use crate::protocol::TestRandom;
-use crate::byte_order::{ProtoByteOrder, ByteOrder};
+use crate::byte_order::{ProtoByteOrder, ByteOrder, LittleEndian};
+use crate::types::{VarInt, VarLong};
+use crate::Deserialize;
+
+pub mod path;
+pub mod snbt;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct NamedTag {
@@ -14,7 +19,11 @@ pub struct NamedTag {
 
 impl NamedTag {
     pub fn root_compound_tag_from_bytes(data: &[u8]) -> DeserializeResult<NamedTag> {
-        read_nbt_data(data)
+        read_nbt_data::<ProtoByteOrder>(data)
+    }
+
+    pub fn root_compound_tag_from_bytes_as<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<'_, NamedTag> {
+        read_nbt_data::<E>(data)
     }
 
     pub fn is_end(&self) -> bool {
@@ -23,6 +32,34 @@ impl NamedTag {
             _ => false,
         }
     }
+
+    pub fn get(&self, name: &str) -> Option<&Tag> {
+        self.payload.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Tag> {
+        self.payload.get_mut(name)
+    }
+
+    pub fn get_path(&self, path: &str) -> Option<&Tag> {
+        self.payload.get_path(path)
+    }
+
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut Tag> {
+        self.payload.get_path_mut(path)
+    }
+
+    pub fn insert<T>(&mut self, name: &str, value: T) -> Result<Option<Tag>, Tag> where T: Into<Tag> {
+        self.payload.insert(name, value)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Tag> {
+        self.payload.remove(name)
+    }
+
+    pub fn get_compound(&self, name: &str) -> Option<&Tag> {
+        self.payload.get_compound(name)
+    }
 }
 
 #[cfg(all(test, feature = "std"))]
@@ -168,6 +205,250 @@ impl TestRandom for Tag {
     }
 }
 
+// compound access
+impl Tag {
+    pub fn is_compound(&self) -> bool {
+        matches!(self, Tag::Compound(_))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Tag> {
+        self.as_compound()?
+            .iter()
+            .find(move |elem| elem.name == name)
+            .map(move |elem| &elem.payload)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Tag> {
+        self.as_compound_mut()?
+            .iter_mut()
+            .find(move |elem| elem.name == name)
+            .map(move |elem| &mut elem.payload)
+    }
+
+    // inserts or replaces the entry with the given name, keeping the position of a replaced entry.
+    // if this tag isn't a compound, the value is handed back as the error
+    pub fn insert<T>(&mut self, name: &str, value: T) -> Result<Option<Tag>, Tag> where T: Into<Tag> {
+        let value = value.into();
+        let entries = match self.as_compound_mut() {
+            Some(entries) => entries,
+            None => return Err(value),
+        };
+
+        if let Some(existing) = entries.iter_mut().find(|elem| elem.name == name) {
+            Ok(Some(core::mem::replace(&mut existing.payload, value)))
+        } else {
+            entries.push(value.with_name(name));
+            Ok(None)
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Tag> {
+        let entries = self.as_compound_mut()?;
+        let idx = entries.iter().position(move |elem| elem.name == name)?;
+        Some(entries.remove(idx).payload)
+    }
+
+    // follows a dot separated list of compound keys, such as "tag.display.Name"
+    pub fn get_path(&self, path: &str) -> Option<&Tag> {
+        let mut at = self;
+        for name in path.split('.') {
+            at = at.get(name)?;
+        }
+
+        Some(at)
+    }
+
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut Tag> {
+        let mut at = self;
+        for name in path.split('.') {
+            at = at.get_mut(name)?;
+        }
+
+        Some(at)
+    }
+
+    // returns the compound itself (rather than its entries) so lookups can be chained
+    pub fn get_compound(&self, name: &str) -> Option<&Tag> {
+        self.get(name).filter(move |tag| tag.is_compound())
+    }
+
+    pub fn as_i8(&self) -> Option<i8> {
+        match self {
+            Tag::Byte(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_i16(&self) -> Option<i16> {
+        match self {
+            Tag::Short(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            Tag::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Tag::Long(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            Tag::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Tag::Double(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_byte_array(&self) -> Option<&[u8]> {
+        match self {
+            Tag::ByteArray(v) => Some(v.as_slice()),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Tag::String(v) => Some(v.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Tag]> {
+        match self {
+            Tag::List(v) => Some(v.as_slice()),
+            _ => None,
+        }
+    }
+
+    pub fn as_list_mut(&mut self) -> Option<&mut Vec<Tag>> {
+        match self {
+            Tag::List(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_compound(&self) -> Option<&[NamedTag]> {
+        match self {
+            Tag::Compound(v) => Some(v.as_slice()),
+            _ => None,
+        }
+    }
+
+    pub fn as_compound_mut(&mut self) -> Option<&mut Vec<NamedTag>> {
+        match self {
+            Tag::Compound(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_int_array(&self) -> Option<&[i32]> {
+        match self {
+            Tag::IntArray(v) => Some(v.as_slice()),
+            _ => None,
+        }
+    }
+
+    pub fn as_long_array(&self) -> Option<&[i64]> {
+        match self {
+            Tag::LongArray(v) => Some(v.as_slice()),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! def_typed_getters {
+    ($($getnam: ident, $asnam: ident -> $ret: ty);+) => {
+        impl Tag {
+            $(pub fn $getnam(&self, name: &str) -> Option<$ret> {
+                self.get(name)?.$asnam()
+            })+
+        }
+
+        impl NamedTag {
+            $(pub fn $getnam(&self, name: &str) -> Option<$ret> {
+                self.payload.$getnam(name)
+            })+
+        }
+    }
+}
+
+def_typed_getters!(
+    get_i8, as_i8 -> i8;
+    get_i16, as_i16 -> i16;
+    get_i32, as_i32 -> i32;
+    get_i64, as_i64 -> i64;
+    get_f32, as_f32 -> f32;
+    get_f64, as_f64 -> f64;
+    get_byte_array, as_byte_array -> &[u8];
+    get_str, as_str -> &str;
+    get_list, as_list -> &[Tag];
+    get_int_array, as_int_array -> &[i32];
+    get_long_array, as_long_array -> &[i64]
+);
+
+macro_rules! def_tag_from {
+    ($($typ: ty => $variant: ident);+) => {
+        $(impl From<$typ> for Tag {
+            fn from(v: $typ) -> Self {
+                Tag::$variant(v)
+            }
+        })+
+    }
+}
+
+def_tag_from!(
+    i8 => Byte;
+    i16 => Short;
+    i32 => Int;
+    i64 => Long;
+    f32 => Float;
+    f64 => Double;
+    Vec<u8> => ByteArray;
+    String => String;
+    Vec<Tag> => List;
+    Vec<NamedTag> => Compound;
+    Vec<i32> => IntArray;
+    Vec<i64> => LongArray
+);
+
+impl From<bool> for Tag {
+    fn from(v: bool) -> Self {
+        Tag::Byte(if v { 1 } else { 0 })
+    }
+}
+
+impl From<&str> for Tag {
+    fn from(v: &str) -> Self {
+        Tag::String(v.to_owned())
+    }
+}
+
+// builds a compound tag, for example nbt!{ "Count" => 1i8, "tag" => nbt!{ "Damage" => 3 } }
+#[macro_export]
+macro_rules! nbt {
+    ($($key: expr => $value: expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut out = $crate::nbt::Tag::Compound(::core::default::Default::default());
+        $(let _ = out.insert($key, $value);)*
+        out
+    }};
+}
+
 fn write_contents<F>(contents: &Vec<F>) -> String
     where
         F: fmt::Display,
@@ -188,45 +469,294 @@ fn write_contents<F>(contents: &Vec<F>) -> String
     )
 }
 
+// encodings
+
+// describes how the numbers and lengths inside of nbt data are encoded. any ByteOrder is an
+// encoding (BigEndian for java edition, LittleEndian for bedrock files), and NetworkLittleEndian
+// is the variant bedrock uses over the network
+pub trait NbtEncoding {
+    fn read_short(data: &[u8]) -> DeserializeResult<'_, i16>;
+
+    fn write_short(v: i16, to: &mut Vec<u8>);
+
+    fn read_int(data: &[u8]) -> DeserializeResult<'_, i32>;
+
+    fn write_int(v: i32, to: &mut Vec<u8>);
+
+    fn read_long(data: &[u8]) -> DeserializeResult<'_, i64>;
+
+    fn write_long(v: i64, to: &mut Vec<u8>);
+
+    fn read_float(data: &[u8]) -> DeserializeResult<'_, f32>;
+
+    fn write_float(v: f32, to: &mut Vec<u8>);
+
+    fn read_double(data: &[u8]) -> DeserializeResult<'_, f64>;
+
+    fn write_double(v: f64, to: &mut Vec<u8>);
+
+    // the length of a list or array
+    fn read_length(data: &[u8]) -> DeserializeResult<'_, i32>;
+
+    fn write_length(v: usize, to: &mut Vec<u8>);
+
+    fn read_string_length(data: &[u8]) -> DeserializeResult<'_, usize>;
+
+    fn write_string_length(v: usize, to: &mut Vec<u8>);
+
+    // how many bytes the writes above produce, so sizes can be worked out without writing anything. shorts, floats
+    // and doubles are always 2, 4 and 8 bytes. the defaults measure by writing into a scratch buffer
+    fn int_size(v: i32) -> usize {
+        let mut out = Vec::new();
+        Self::write_int(v, &mut out);
+        out.len()
+    }
+
+    fn long_size(v: i64) -> usize {
+        let mut out = Vec::new();
+        Self::write_long(v, &mut out);
+        out.len()
+    }
+
+    fn length_size(v: usize) -> usize {
+        let mut out = Vec::new();
+        Self::write_length(v, &mut out);
+        out.len()
+    }
+
+    fn string_length_size(v: usize) -> usize {
+        let mut out = Vec::new();
+        Self::write_string_length(v, &mut out);
+        out.len()
+    }
+}
+
+impl<B> NbtEncoding for B where B: ByteOrder {
+    fn read_short(data: &[u8]) -> DeserializeResult<'_, i16> {
+        <B as ByteOrder>::read_short(data)
+    }
+
+    fn write_short(v: i16, to: &mut Vec<u8>) {
+        to.extend_from_slice(&<B as ByteOrder>::write_short(v));
+    }
+
+    fn read_int(data: &[u8]) -> DeserializeResult<'_, i32> {
+        <B as ByteOrder>::read_int(data)
+    }
+
+    fn write_int(v: i32, to: &mut Vec<u8>) {
+        to.extend_from_slice(&<B as ByteOrder>::write_int(v));
+    }
+
+    fn read_long(data: &[u8]) -> DeserializeResult<'_, i64> {
+        <B as ByteOrder>::read_long(data)
+    }
+
+    fn write_long(v: i64, to: &mut Vec<u8>) {
+        to.extend_from_slice(&<B as ByteOrder>::write_long(v));
+    }
+
+    fn read_float(data: &[u8]) -> DeserializeResult<'_, f32> {
+        <B as ByteOrder>::read_float(data)
+    }
+
+    fn write_float(v: f32, to: &mut Vec<u8>) {
+        to.extend_from_slice(&<B as ByteOrder>::write_float(v));
+    }
+
+    fn read_double(data: &[u8]) -> DeserializeResult<'_, f64> {
+        <B as ByteOrder>::read_double(data)
+    }
+
+    fn write_double(v: f64, to: &mut Vec<u8>) {
+        to.extend_from_slice(&<B as ByteOrder>::write_double(v));
+    }
+
+    fn read_length(data: &[u8]) -> DeserializeResult<'_, i32> {
+        <B as ByteOrder>::read_int(data)
+    }
+
+    fn write_length(v: usize, to: &mut Vec<u8>) {
+        to.extend_from_slice(&<B as ByteOrder>::write_uint(v as u32));
+    }
+
+    fn read_string_length(data: &[u8]) -> DeserializeResult<'_, usize> {
+        Ok(<B as ByteOrder>::read_ushort(data)?.map(move |v| v as usize))
+    }
+
+    fn write_string_length(v: usize, to: &mut Vec<u8>) {
+        to.extend_from_slice(&<B as ByteOrder>::write_ushort(v as u16));
+    }
+
+    fn int_size(_: i32) -> usize {
+        4
+    }
+
+    fn long_size(_: i64) -> usize {
+        8
+    }
+
+    fn length_size(_: usize) -> usize {
+        4
+    }
+
+    fn string_length_size(_: usize) -> usize {
+        2
+    }
+}
+
+// bedrock's network nbt: ints, longs and lengths are zigzag encoded var nums, string lengths are
+// unsigned var ints, and everything else is little endian
+pub struct NetworkLittleEndian;
+
+impl NbtEncoding for NetworkLittleEndian {
+    fn read_short(data: &[u8]) -> DeserializeResult<'_, i16> {
+        <LittleEndian as ByteOrder>::read_short(data)
+    }
+
+    fn write_short(v: i16, to: &mut Vec<u8>) {
+        to.extend_from_slice(&<LittleEndian as ByteOrder>::write_short(v));
+    }
+
+    fn read_int(data: &[u8]) -> DeserializeResult<'_, i32> {
+        Ok(VarInt::mc_deserialize(data)?.map(move |v| {
+            let raw = v.0 as u32;
+            ((raw >> 1) as i32) ^ -((raw & 1) as i32)
+        }))
+    }
+
+    fn write_int(v: i32, to: &mut Vec<u8>) {
+        write_var_num(((v << 1) ^ (v >> 31)) as u32 as u64, to);
+    }
+
+    fn read_long(data: &[u8]) -> DeserializeResult<'_, i64> {
+        Ok(VarLong::mc_deserialize(data)?.map(move |v| {
+            let raw = v.0 as u64;
+            ((raw >> 1) as i64) ^ -((raw & 1) as i64)
+        }))
+    }
+
+    fn write_long(v: i64, to: &mut Vec<u8>) {
+        write_var_num(((v << 1) ^ (v >> 63)) as u64, to);
+    }
+
+    fn read_float(data: &[u8]) -> DeserializeResult<'_, f32> {
+        <LittleEndian as ByteOrder>::read_float(data)
+    }
+
+    fn write_float(v: f32, to: &mut Vec<u8>) {
+        to.extend_from_slice(&<LittleEndian as ByteOrder>::write_float(v));
+    }
+
+    fn read_double(data: &[u8]) -> DeserializeResult<'_, f64> {
+        <LittleEndian as ByteOrder>::read_double(data)
+    }
+
+    fn write_double(v: f64, to: &mut Vec<u8>) {
+        to.extend_from_slice(&<LittleEndian as ByteOrder>::write_double(v));
+    }
+
+    fn read_length(data: &[u8]) -> DeserializeResult<'_, i32> {
+        Self::read_int(data)
+    }
+
+    fn write_length(v: usize, to: &mut Vec<u8>) {
+        Self::write_int(v as i32, to)
+    }
+
+    fn read_string_length(data: &[u8]) -> DeserializeResult<'_, usize> {
+        Ok(VarInt::mc_deserialize(data)?.map(move |v| v.0 as u32 as usize))
+    }
+
+    fn write_string_length(v: usize, to: &mut Vec<u8>) {
+        write_var_num(v as u32 as u64, to)
+    }
+
+    fn int_size(v: i32) -> usize {
+        var_num_size(((v << 1) ^ (v >> 31)) as u32 as u64)
+    }
+
+    fn long_size(v: i64) -> usize {
+        var_num_size(((v << 1) ^ (v >> 63)) as u64)
+    }
+
+    fn length_size(v: usize) -> usize {
+        Self::int_size(v as i32)
+    }
+
+    fn string_length_size(v: usize) -> usize {
+        var_num_size(v as u32 as u64)
+    }
+}
+
+fn write_var_num(mut v: u64, to: &mut Vec<u8>) {
+    loop {
+        let byte = (v & 0x7F) as u8;
+        v >>= 7;
+        if v == 0 {
+            to.push(byte);
+            return;
+        }
+        to.push(byte | 0x80);
+    }
+}
+
+fn var_num_size(mut v: u64) -> usize {
+    let mut size = 1;
+    while v >= 0x80 {
+        v >>= 7;
+        size += 1;
+    }
+    size
+}
+
 // deserialization first
 
 // reads from the root level
-fn read_nbt_data(data: &[u8]) -> DeserializeResult<NamedTag> {
+fn read_nbt_data<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<NamedTag> {
     let Deserialized { value: tag_type_id, data: _ } = ProtoByteOrder::read_ubyte(data)?;
     match tag_type_id {
-        0x0A => read_named_tag(data),
+        0x0A => read_named_tag_as::<E>(data),
         other => Err(DeserializeErr::NbtInvalidStartTag(other)),
     }
 }
 
 // reads any named tag: read id -> read name -> read tag with id -> name tag with name
 pub fn read_named_tag(data: &[u8]) -> DeserializeResult<NamedTag> {
+    read_named_tag_as::<ProtoByteOrder>(data)
+}
+
+pub fn read_named_tag_as<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<'_, NamedTag> {
     let Deserialized { value: tag_type_id, data } = ProtoByteOrder::read_ubyte(data)?;
     if tag_type_id == 0x00 {
         // tag end
         Deserialized::ok(Tag::End.with_name(""), data)
     } else {
-        let Deserialized { value: name, data } = read_string(data)?;
-        Ok(read_tag(tag_type_id, data)?.map(move |payload| NamedTag { name, payload }))
+        let Deserialized { value: name, data } = read_string::<E>(data)?;
+        Ok(read_tag_as::<E>(tag_type_id, data)?.map(move |payload| NamedTag { name, payload }))
     }
 }
 
 // reads any tag (given it's id)
 pub fn read_tag(tag_type_id: u8, data: &[u8]) -> DeserializeResult<Tag> {
+    read_tag_as::<ProtoByteOrder>(tag_type_id, data)
+}
+
+pub fn read_tag_as<E: NbtEncoding>(tag_type_id: u8, data: &[u8]) -> DeserializeResult<'_, Tag> {
     match tag_type_id {
         0x00 => Deserialized::ok(Tag::End, data),
         0x01 => read_tag_byte(data),
-        0x02 => read_tag_short(data),
-        0x03 => read_tag_int(data),
-        0x04 => read_tag_long(data),
-        0x05 => read_tag_float(data),
-        0x06 => read_tag_double(data),
-        0x07 => read_tag_byte_array(data),
-        0x08 => read_tag_string(data),
-        0x09 => read_tag_list(data),
-        0x0A => read_tag_compound(data),
-        0x0B => read_tag_int_array(data),
-        0x0C => read_tag_long_array(data),
+        0x02 => read_tag_short::<E>(data),
+        0x03 => read_tag_int::<E>(data),
+        0x04 => read_tag_long::<E>(data),
+        0x05 => read_tag_float::<E>(data),
+        0x06 => read_tag_double::<E>(data),
+        0x07 => read_tag_byte_array::<E>(data),
+        0x08 => read_tag_string::<E>(data),
+        0x09 => read_tag_list::<E>(data),
+        0x0A => read_tag_compound::<E>(data),
+        0x0B => read_tag_int_array::<E>(data),
+        0x0C => read_tag_long_array::<E>(data),
         other => Err(DeserializeErr::NbtUnknownTagType(other)),
     }
 }
@@ -235,39 +765,39 @@ fn read_tag_byte(data: &[u8]) -> DeserializeResult<Tag> {
     Ok(ProtoByteOrder::read_byte(data)?.map(Tag::Byte))
 }
 
-fn read_tag_short(data: &[u8]) -> DeserializeResult<Tag> {
-    Ok(ProtoByteOrder::read_short(data)?.map(Tag::Short))
+fn read_tag_short<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<Tag> {
+    Ok(E::read_short(data)?.map(Tag::Short))
 }
 
-fn read_tag_int(data: &[u8]) -> DeserializeResult<Tag> {
-    Ok(ProtoByteOrder::read_int(data)?.map(Tag::Int))
+fn read_tag_int<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<Tag> {
+    Ok(E::read_int(data)?.map(Tag::Int))
 }
 
-fn read_tag_long(data: &[u8]) -> DeserializeResult<Tag> {
-    Ok(ProtoByteOrder::read_long(data)?.map(Tag::Long))
+fn read_tag_long<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<Tag> {
+    Ok(E::read_long(data)?.map(Tag::Long))
 }
 
-fn read_tag_float(data: &[u8]) -> DeserializeResult<Tag> {
-    Ok(ProtoByteOrder::read_float(data)?.map(Tag::Float))
+fn read_tag_float<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<Tag> {
+    Ok(E::read_float(data)?.map(Tag::Float))
 }
 
-fn read_tag_double(data: &[u8]) -> DeserializeResult<Tag> {
-    Ok(ProtoByteOrder::read_double(data)?.map(Tag::Double))
+fn read_tag_double<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<Tag> {
+    Ok(E::read_double(data)?.map(Tag::Double))
 }
 
-fn read_tag_byte_array(data: &[u8]) -> DeserializeResult<Tag> {
-    Ok(ProtoByteOrder::read_int(data)?
+fn read_tag_byte_array<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<Tag> {
+    Ok(E::read_length(data)?
         .and_then(move |size, rest| take(size as usize, rest))?
         .map(move |arr| Tag::ByteArray(Vec::from(arr))))
 }
 
-fn read_tag_string(data: &[u8]) -> DeserializeResult<Tag> {
-    Ok(read_string(data)?.map(move |str| Tag::String(str)))
+fn read_tag_string<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<Tag> {
+    Ok(read_string::<E>(data)?.map(Tag::String))
 }
 
-fn read_tag_list(data: &[u8]) -> DeserializeResult<Tag> {
+fn read_tag_list<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<Tag> {
     let Deserialized { value: contents_tag_type_id, data } = ProtoByteOrder::read_ubyte(data)?;
-    let Deserialized { value: list_length, data } = ProtoByteOrder::read_int(data)?;
+    let Deserialized { value: list_length, data } = E::read_length(data)?;
     if list_length == 0 {
         Deserialized::ok(Tag::List(vec![]), data)
     } else {
@@ -275,7 +805,7 @@ fn read_tag_list(data: &[u8]) -> DeserializeResult<Tag> {
         let mut remaining_data = data;
         for _ in 0..list_length {
             let Deserialized { value: element, data: rest } =
-                read_tag(contents_tag_type_id, &remaining_data)?;
+                read_tag_as::<E>(contents_tag_type_id, &remaining_data)?;
 
             out_vec.push(element);
             remaining_data = rest;
@@ -285,14 +815,14 @@ fn read_tag_list(data: &[u8]) -> DeserializeResult<Tag> {
     }
 }
 
-fn read_tag_compound(data: &[u8]) -> DeserializeResult<Tag> {
+fn read_tag_compound<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<Tag> {
     let mut out = Vec::new();
     let mut remaining_data = data;
     loop {
         let Deserialized {
             value: elem,
             data: rest,
-        } = read_named_tag(remaining_data)?;
+        } = read_named_tag_as::<E>(remaining_data)?;
         remaining_data = rest;
         if elem.is_end() {
             break;
@@ -303,24 +833,25 @@ fn read_tag_compound(data: &[u8]) -> DeserializeResult<Tag> {
     Deserialized::ok(Tag::Compound(out), remaining_data)
 }
 
-fn read_tag_int_array(data: &[u8]) -> DeserializeResult<Tag> {
-    read_array_tag(data, ProtoByteOrder::read_int, Tag::IntArray)
+fn read_tag_int_array<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<Tag> {
+    read_array_tag::<E, _, _, _>(data, E::read_int, Tag::IntArray)
 }
 
-fn read_tag_long_array(data: &[u8]) -> DeserializeResult<Tag> {
-    read_array_tag(data, ProtoByteOrder::read_long, Tag::LongArray)
+fn read_tag_long_array<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<Tag> {
+    read_array_tag::<E, _, _, _>(data, E::read_long, Tag::LongArray)
 }
 
-fn read_array_tag<'a, R, F, M>(
+fn read_array_tag<'a, E, R, F, M>(
     data: &'a [u8],
     parser: F,
     finalizer: M,
 ) -> DeserializeResult<'a, Tag>
     where
+        E: NbtEncoding,
         F: Fn(&'a [u8]) -> DeserializeResult<'a, R>,
         M: Fn(Vec<R>) -> Tag,
 {
-    let Deserialized { value: count, data } = ProtoByteOrder::read_int(data)?.map(move |v| v as i32);
+    let Deserialized { value: count, data } = E::read_length(data)?;
     if count < 0 {
         Err(DeserializeErr::NbtBadLength(count as isize))
     } else {
@@ -339,9 +870,9 @@ fn read_array_tag<'a, R, F, M>(
     }
 }
 
-fn read_string(data: &[u8]) -> DeserializeResult<String> {
-    ProtoByteOrder::read_short(data)?
-        .and_then(move |length, data| take(length as usize, data))?
+fn read_string<E: NbtEncoding>(data: &[u8]) -> DeserializeResult<String> {
+    E::read_string_length(data)?
+        .and_then(move |length, data| take(length, data))?
         .try_map(move |bytes| {
             String::from_utf8(Vec::from(bytes))
                 .map_err(move |err| DeserializeErr::BadStringEncoding(err))
@@ -351,24 +882,43 @@ fn read_string(data: &[u8]) -> DeserializeResult<String> {
 // serialize
 impl NamedTag {
     pub fn bytes(&self) -> Vec<u8> {
+        self.bytes_as::<ProtoByteOrder>()
+    }
+
+    pub fn bytes_as<E: NbtEncoding>(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_to::<E>(&mut out);
+        out
+    }
+
+    // the length of bytes(), worked out without writing anything
+    pub fn serialized_size(&self) -> usize {
+        self.serialized_size_as::<ProtoByteOrder>()
+    }
+
+    // the length of bytes_as::<E>()
+    pub fn serialized_size_as<E: NbtEncoding>(&self) -> usize {
+        match self.payload.id() {
+            0x00 => 1,
+            _ => 1 + E::string_length_size(self.name.len()) + self.name.len() + self.payload.serialized_size_as::<E>(),
+        }
+    }
+
+    fn write_to<E: NbtEncoding>(&self, out: &mut Vec<u8>) {
         let type_id = self.payload.id();
-        if type_id == 0x00 {
-            vec![0x00]
-        } else {
-            let payload_bytes = self.payload.bytes();
-            let name_len = self.name.len();
-            let name_len_bytes = ProtoByteOrder::write_ushort(name_len as u16);
-            let mut out =
-                Vec::with_capacity(1 + name_len_bytes.len() + name_len + payload_bytes.len());
-            out.push(type_id);
-            out.extend_from_slice(&name_len_bytes);
-            out.extend(self.name.bytes());
-            out.extend(payload_bytes);
-            out
+        out.push(type_id);
+        if type_id != 0x00 {
+            write_string::<E>(self.name.as_str(), out);
+            self.payload.write_to::<E>(out);
         }
     }
 }
 
+fn write_string<E: NbtEncoding>(v: &str, out: &mut Vec<u8>) {
+    E::write_string_length(v.len(), out);
+    out.extend_from_slice(v.as_bytes());
+}
+
 impl Tag {
     pub fn id(&self) -> u8 {
         match self {
@@ -389,29 +939,51 @@ impl Tag {
     }
 
     pub fn bytes(&self) -> Vec<u8> {
+        self.bytes_as::<ProtoByteOrder>()
+    }
+
+    pub fn bytes_as<E: NbtEncoding>(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_to::<E>(&mut out);
+        out
+    }
+
+    pub fn serialized_size(&self) -> usize {
+        self.serialized_size_as::<ProtoByteOrder>()
+    }
+
+    pub fn serialized_size_as<E: NbtEncoding>(&self) -> usize {
         match self {
-            Tag::Byte(b) => vec![*b as u8],
-            Tag::Short(v) => Vec::from(ProtoByteOrder::write_short(*v)),
-            Tag::Int(v) => Vec::from(ProtoByteOrder::write_int(*v)),
-            Tag::Long(v) => Vec::from(ProtoByteOrder::write_long(*v)),
-            Tag::Float(v) => Vec::from(ProtoByteOrder::write_float(*v)),
-            Tag::Double(v) => Vec::from(ProtoByteOrder::write_double(*v)),
+            Tag::Byte(_) => 1,
+            Tag::Short(_) => 2,
+            Tag::Int(v) => E::int_size(*v),
+            Tag::Long(v) => E::long_size(*v),
+            Tag::Float(_) => 4,
+            Tag::Double(_) => 8,
+            Tag::ByteArray(v) => E::length_size(v.len()) + v.len(),
+            Tag::String(v) => E::string_length_size(v.len()) + v.len(),
+            Tag::List(v) => 1 + E::length_size(v.len()) + v.iter().map(move |elem| elem.serialized_size_as::<E>()).sum::<usize>(),
+            // and the end tag
+            Tag::Compound(v) => v.iter().map(move |elem| elem.serialized_size_as::<E>()).sum::<usize>() + 1,
+            Tag::IntArray(v) => E::length_size(v.len()) + v.iter().map(move |v| E::int_size(*v)).sum::<usize>(),
+            Tag::LongArray(v) => E::length_size(v.len()) + v.iter().map(move |v| E::long_size(*v)).sum::<usize>(),
+            Tag::End => 0,
+        }
+    }
+
+    fn write_to<E: NbtEncoding>(&self, out: &mut Vec<u8>) {
+        match self {
+            Tag::Byte(b) => out.push(*b as u8),
+            Tag::Short(v) => E::write_short(*v, out),
+            Tag::Int(v) => E::write_int(*v, out),
+            Tag::Long(v) => E::write_long(*v, out),
+            Tag::Float(v) => E::write_float(*v, out),
+            Tag::Double(v) => E::write_double(*v, out),
             Tag::ByteArray(v) => {
-                let n = v.len();
-                let mut out = Vec::with_capacity(n + 4);
-                let size_bytes = ProtoByteOrder::write_uint(n as u32);
-                out.extend_from_slice(&size_bytes);
-                out.extend(v);
-                out
-            }
-            Tag::String(v) => {
-                let n = v.len();
-                let mut out = Vec::with_capacity(n + 2);
-                let size_bytes = ProtoByteOrder::write_ushort(n as u16);
-                out.extend_from_slice(&size_bytes);
-                out.extend(v.bytes());
-                out
+                E::write_length(v.len(), out);
+                out.extend_from_slice(v);
             }
+            Tag::String(v) => write_string::<E>(v.as_str(), out),
             Tag::List(v) => {
                 let count = v.len();
                 let elem_id = {
@@ -434,44 +1006,31 @@ impl Tag {
                     }
                 };
 
-                let mut out = Vec::new();
                 out.push(elem_id);
-                let count_bytes = ProtoByteOrder::write_uint(count as u32);
-                out.extend_from_slice(&count_bytes);
-                out.extend(v.iter().flat_map(move |elem| elem.bytes().into_iter()));
-                out
+                E::write_length(count, out);
+                for elem in v {
+                    elem.write_to::<E>(out);
+                }
             }
             Tag::Compound(v) => {
-                let mut out = Vec::new();
                 for elem in v {
-                    out.extend(elem.bytes());
+                    elem.write_to::<E>(out);
                 }
-                out.extend(Tag::End.with_name("").bytes());
-                out
+                Tag::End.with_name("").write_to::<E>(out);
             }
             Tag::IntArray(v) => {
-                let n = v.len();
-                let mut out = Vec::with_capacity(4 + (4 * n));
-                let n_bytes = ProtoByteOrder::write_uint(n as u32);
-                out.extend_from_slice(&n_bytes);
+                E::write_length(v.len(), out);
                 for value in v {
-                    let bytes = ProtoByteOrder::write_int(*value);
-                    out.extend_from_slice(&bytes);
+                    E::write_int(*value, out);
                 }
-                out
             }
             Tag::LongArray(v) => {
-                let n = v.len();
-                let mut out = Vec::with_capacity(4 + (8 * n));
-                let n_bytes = ProtoByteOrder::write_uint(n as u32);
-                out.extend_from_slice(&n_bytes);
+                E::write_length(v.len(), out);
                 for value in v {
-                    let bytes = ProtoByteOrder::write_long(*value);
-                    out.extend_from_slice(&bytes);
+                    E::write_long(*value, out);
                 }
-                out
             }
-            Tag::End => Vec::default(),
+            Tag::End => {}
         }
     }
 }
@@ -533,6 +1092,7 @@ mod tests {
         let (unzipped, result) = read_bigtest_with_bytes();
         let serialized = result.bytes();
         assert_eq!(unzipped, serialized);
+        assert_eq!(result.serialized_size(), serialized.len());
         let Deserialized {
             value: unserialized,
             data: _,
@@ -583,6 +1143,116 @@ mod tests {
         assert_eq!(original, unserialized);
     }
 
+    #[test]
+    fn test_little_endian_bytes() {
+        let original = Tag::Compound(vec![Tag::Int(1).with_name("a"), Tag::Short(-2).with_name("b")]).with_name("");
+        let bytes = original.bytes_as::<LittleEndian>();
+        assert_eq!(bytes, vec![
+            0x0A, 0x00, 0x00,
+            0x03, 0x01, 0x00, b'a', 0x01, 0x00, 0x00, 0x00,
+            0x02, 0x01, 0x00, b'b', 0xFE, 0xFF,
+            0x00,
+        ]);
+
+        let Deserialized { value: unserialized, data: rest } =
+            NamedTag::root_compound_tag_from_bytes_as::<LittleEndian>(bytes.as_slice()).expect("deserialize le nbt");
+        assert!(rest.is_empty());
+        assert_eq!(original, unserialized);
+    }
+
+    #[test]
+    fn test_network_little_endian_bytes() {
+        let original = Tag::Compound(vec![
+            Tag::Int(-1).with_name("a"),
+            Tag::Long(150).with_name("b"),
+            Tag::IntArray(vec![1, -2]).with_name("c"),
+        ]).with_name("");
+        let bytes = original.bytes_as::<NetworkLittleEndian>();
+        assert_eq!(bytes, vec![
+            0x0A, 0x00,
+            0x03, 0x01, b'a', 0x01,
+            0x04, 0x01, b'b', 0xAC, 0x02,
+            0x0B, 0x01, b'c', 0x04, 0x02, 0x03,
+            0x00,
+        ]);
+        assert_eq!(original.serialized_size_as::<NetworkLittleEndian>(), bytes.len());
+
+        let Deserialized { value: unserialized, data: rest } =
+            NamedTag::root_compound_tag_from_bytes_as::<NetworkLittleEndian>(bytes.as_slice()).expect("deserialize network nbt");
+        assert!(rest.is_empty());
+        assert_eq!(original, unserialized);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_bigtest_other_encodings() {
+        fn round_trip<E: NbtEncoding>(original: &NamedTag) {
+            let bytes = original.bytes_as::<E>();
+            assert_eq!(original.serialized_size_as::<E>(), bytes.len());
+            let Deserialized { value: unserialized, data: rest } =
+                NamedTag::root_compound_tag_from_bytes_as::<E>(bytes.as_slice()).expect("deserialize nbt");
+            assert!(rest.is_empty());
+            assert_eq!(&unserialized, original);
+        }
+
+        let bigtest = read_bigtest();
+        round_trip::<LittleEndian>(&bigtest);
+        round_trip::<NetworkLittleEndian>(&bigtest);
+    }
+
+    #[test]
+    fn test_compound_access() {
+        let mut item = nbt!{
+            "id" => "minecraft:diamond_sword",
+            "Count" => 1i8,
+            "tag" => nbt!{
+                "Damage" => 3,
+                "display" => nbt!{ "Name" => "{\"text\":\"Sword\"}" },
+            },
+        };
+
+        assert_eq!(item.get_str("id"), Some("minecraft:diamond_sword"));
+        assert_eq!(item.get_i8("Count"), Some(1));
+        assert_eq!(item.get_i32("Count"), None);
+        assert_eq!(item.get_compound("tag").and_then(move |tag| tag.get_i32("Damage")), Some(3));
+        assert_eq!(item.get_compound("id"), None);
+        assert_eq!(item.get_path("tag.display.Name").and_then(Tag::as_str), Some("{\"text\":\"Sword\"}"));
+        assert_eq!(item.get_path("tag.display.Lore"), None);
+
+        assert_eq!(item.insert("Count", 5i8), Ok(Some(Tag::Byte(1))));
+        assert_eq!(item.insert("Slot", 2i8), Ok(None));
+        assert_eq!(Tag::Int(1).insert("Slot", 2i8), Err(Tag::Byte(2)));
+        assert_eq!(item.remove("id"), Some(Tag::String("minecraft:diamond_sword".to_owned())));
+        assert_eq!(item.remove("id"), None);
+
+        let names: Vec<&str> = item.as_compound()
+            .expect("is compound")
+            .iter()
+            .map(move |elem| elem.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Count", "tag", "Slot"]);
+
+        *item.get_path_mut("tag.Damage").expect("has damage") = Tag::Int(10);
+        assert_eq!(item.get_path("tag.Damage"), Some(&Tag::Int(10)));
+    }
+
+    #[test]
+    fn test_nbt_macro_round_trip() {
+        let original = nbt!{
+            "ints" => vec![1, 2, 3],
+            "list" => vec![Tag::Short(1), Tag::Short(2)],
+            "flag" => true,
+        }.with_name("root");
+
+        assert_eq!(original.get_int_array("ints"), Some(&[1, 2, 3][..]));
+        assert_eq!(original.get_i8("flag"), Some(1));
+
+        let bytes = original.bytes();
+        let Deserialized { value: unserialized, data: _ } =
+            NamedTag::root_compound_tag_from_bytes(bytes.as_slice()).expect("deserialize built nbt");
+        assert_eq!(original, unserialized);
+    }
+
     #[cfg(feature = "std")]
     #[test]
     fn test_display() {
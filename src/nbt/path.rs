@@ -0,0 +1,573 @@
+use super::{NamedTag, Tag};
+use super::snbt::{SnbtErr, SnbtReader};
+use alloc::{string::String, vec::Vec, borrow::Cow, fmt};
+
+// an nbt path, as accepted by the minecraft:nbt_path command argument
+// (for example Inventory[{Slot:0b}].tag.display.Name)
+#[derive(Clone, Debug, PartialEq)]
+pub struct NbtPath {
+    nodes: Vec<NbtPathNode>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NbtPathNode {
+    // {...} at the start of a path, matches the root itself
+    MatchRootObject(Tag),
+    // name
+    Named(String),
+    // name{...}
+    MatchObject(String, Tag),
+    // []
+    AllElements,
+    // [n], negative indexes count from the end
+    Index(i32),
+    // [{...}]
+    MatchElement(Tag),
+}
+
+pub enum NbtPathErr {
+    Eof,
+    Unexpected(usize, char),
+    EmptyName(usize),
+    InvalidIndex(usize),
+    BadCompound(SnbtErr),
+}
+
+impl fmt::Display for NbtPathErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use NbtPathErr::*;
+        match self {
+            Eof => f.write_str("nbt path: unexpected end of input"),
+            Unexpected(at, c) => f.write_fmt(format_args!("nbt path: unexpected {:?} at {}", c, at)),
+            EmptyName(at) => f.write_fmt(format_args!("nbt path: expected a name at {}", at)),
+            InvalidIndex(at) => f.write_fmt(format_args!("nbt path: invalid index at {}", at)),
+            BadCompound(err) => f.write_fmt(format_args!("nbt path: bad compound filter: {:?}", err)),
+        }
+    }
+}
+
+impl fmt::Debug for NbtPathErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <dyn fmt::Display>::fmt(self, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NbtPathErr {}
+
+impl From<SnbtErr> for NbtPathErr {
+    fn from(err: SnbtErr) -> Self {
+        NbtPathErr::BadCompound(err)
+    }
+}
+
+impl NbtPath {
+    pub fn parse(text: &str) -> Result<Self, NbtPathErr> {
+        let source: Vec<char> = text.chars().collect();
+        let mut reader = SnbtReader::new(source.as_slice());
+        let mut nodes = Vec::new();
+        let mut is_root = true;
+        loop {
+            nodes.push(parse_node(&mut reader, is_root)?);
+            is_root = false;
+            match reader.peek() {
+                None => break,
+                Some('[') | Some('{') => {}
+                Some('.') => reader.at += 1,
+                Some(other) => return Err(NbtPathErr::Unexpected(reader.at, other)),
+            }
+        }
+
+        Ok(Self { nodes })
+    }
+
+    pub fn nodes(&self) -> &[NbtPathNode] {
+        self.nodes.as_slice()
+    }
+
+    // every tag the path resolves to, array elements are returned as owned tags
+    pub fn get<'a>(&self, root: &'a NamedTag) -> Vec<Cow<'a, Tag>> {
+        let mut current = alloc::vec![Cow::Borrowed(&root.payload)];
+        for node in &self.nodes {
+            let mut next = Vec::new();
+            for tag in &current {
+                if let Cow::Borrowed(tag) = tag {
+                    node.collect(tag, &mut next);
+                }
+            }
+            current = next;
+        }
+
+        current
+    }
+
+    // replaces every tag the path resolves to with value, creating missing parents along the way.
+    // returns the number of tags that changed
+    pub fn set(&self, root: &mut NamedTag, value: Tag) -> usize {
+        let (last, parents) = self.nodes.split_last().expect("paths are never empty");
+        visit_parents_mut(&mut root.payload, parents, last, true, &mut |parent| last.set(parent, &value))
+    }
+
+    // removes every tag the path resolves to, returning how many were removed
+    pub fn remove(&self, root: &mut NamedTag) -> usize {
+        let (last, parents) = self.nodes.split_last().expect("paths are never empty");
+        visit_parents_mut(&mut root.payload, parents, last, false, &mut |parent| last.remove(parent))
+    }
+}
+
+fn parse_node(reader: &mut SnbtReader, is_root: bool) -> Result<NbtPathNode, NbtPathErr> {
+    let at = reader.at;
+    match reader.peek() {
+        None => Err(NbtPathErr::Eof),
+        Some('{') => {
+            if !is_root {
+                return Err(NbtPathErr::Unexpected(at, '{'));
+            }
+            Ok(NbtPathNode::MatchRootObject(reader.read_compound()?))
+        }
+        Some('[') => {
+            reader.at += 1;
+            let out = match reader.peek() {
+                Some('{') => NbtPathNode::MatchElement(reader.read_compound()?),
+                Some(']') => NbtPathNode::AllElements,
+                _ => NbtPathNode::Index(parse_index(reader)?),
+            };
+            match reader.next() {
+                Ok(']') => Ok(out),
+                Ok(other) => Err(NbtPathErr::Unexpected(reader.at - 1, other)),
+                Err(_) => Err(NbtPathErr::Eof),
+            }
+        }
+        Some('"') => {
+            let name = reader.read_quoted_string()?;
+            parse_object_node(reader, name)
+        }
+        Some(_) => {
+            let mut name = String::new();
+            while let Some(c) = reader.peek() {
+                if c == ' ' || c == '"' || c == '[' || c == ']' || c == '.' || c == '{' || c == '}' {
+                    break;
+                }
+                name.push(c);
+                reader.at += 1;
+            }
+
+            if name.is_empty() {
+                Err(NbtPathErr::EmptyName(at))
+            } else {
+                parse_object_node(reader, name)
+            }
+        }
+    }
+}
+
+fn parse_object_node(reader: &mut SnbtReader, name: String) -> Result<NbtPathNode, NbtPathErr> {
+    if reader.peek() == Some('{') {
+        Ok(NbtPathNode::MatchObject(name, reader.read_compound()?))
+    } else {
+        Ok(NbtPathNode::Named(name))
+    }
+}
+
+fn parse_index(reader: &mut SnbtReader) -> Result<i32, NbtPathErr> {
+    let at = reader.at;
+    let mut raw = String::new();
+    while let Some(c) = reader.peek() {
+        if !(c.is_ascii_digit() || c == '-') {
+            break;
+        }
+        raw.push(c);
+        reader.at += 1;
+    }
+
+    raw.parse().map_err(move |_| NbtPathErr::InvalidIndex(at))
+}
+
+fn visit_parents_mut(
+    tag: &mut Tag,
+    nodes: &[NbtPathNode],
+    last: &NbtPathNode,
+    create: bool,
+    f: &mut dyn FnMut(&mut Tag) -> usize,
+) -> usize {
+    match nodes.split_first() {
+        None => f(tag),
+        Some((node, rest)) => {
+            let next = rest.first().unwrap_or(last);
+            let mut count = 0;
+            let preferred = if create { Some(next.preferred_parent()) } else { None };
+            node.for_each_mut(tag, preferred, &mut |child| {
+                count += visit_parents_mut(child, rest, last, create, f);
+            });
+            count
+        }
+    }
+}
+
+impl NbtPathNode {
+    fn collect<'a>(&self, tag: &'a Tag, out: &mut Vec<Cow<'a, Tag>>) {
+        use NbtPathNode::*;
+        match self {
+            MatchRootObject(pattern) => if matches(pattern, tag) {
+                out.push(Cow::Borrowed(tag));
+            },
+            Named(name) => if let Some(child) = tag.get(name) {
+                out.push(Cow::Borrowed(child));
+            },
+            MatchObject(name, pattern) => if let Some(child) = tag.get(name) {
+                if matches(pattern, child) {
+                    out.push(Cow::Borrowed(child));
+                }
+            },
+            AllElements => match tag {
+                Tag::List(elems) => out.extend(elems.iter().map(Cow::Borrowed)),
+                Tag::ByteArray(elems) => out.extend(elems.iter().map(move |v| Cow::Owned(Tag::Byte(*v as i8)))),
+                Tag::IntArray(elems) => out.extend(elems.iter().map(move |v| Cow::Owned(Tag::Int(*v)))),
+                Tag::LongArray(elems) => out.extend(elems.iter().map(move |v| Cow::Owned(Tag::Long(*v)))),
+                _ => {}
+            },
+            Index(idx) => {
+                let idx = *idx;
+                match tag {
+                    Tag::List(elems) => if let Some(elem) = resolve_index(idx, elems.len()).map(move |i| &elems[i]) {
+                        out.push(Cow::Borrowed(elem));
+                    },
+                    Tag::ByteArray(elems) => if let Some(i) = resolve_index(idx, elems.len()) {
+                        out.push(Cow::Owned(Tag::Byte(elems[i] as i8)));
+                    },
+                    Tag::IntArray(elems) => if let Some(i) = resolve_index(idx, elems.len()) {
+                        out.push(Cow::Owned(Tag::Int(elems[i])));
+                    },
+                    Tag::LongArray(elems) => if let Some(i) = resolve_index(idx, elems.len()) {
+                        out.push(Cow::Owned(Tag::Long(elems[i])));
+                    },
+                    _ => {}
+                }
+            }
+            MatchElement(pattern) => if let Tag::List(elems) = tag {
+                out.extend(elems.iter().filter(move |elem| matches(pattern, elem)).map(Cow::Borrowed));
+            },
+        }
+    }
+
+    // visits the children of tag this node selects. when create is set, missing children are
+    // created the way vanilla does so that a following set has somewhere to write to
+    fn for_each_mut(&self, tag: &mut Tag, create: Option<Tag>, f: &mut dyn FnMut(&mut Tag)) {
+        use NbtPathNode::*;
+        match self {
+            MatchRootObject(pattern) => if matches(pattern, tag) {
+                f(tag)
+            },
+            Named(name) => {
+                if let (Some(created), true) = (create, tag.is_compound()) {
+                    if tag.get(name).is_none() {
+                        let _ = tag.insert(name, created);
+                    }
+                }
+
+                if let Some(child) = tag.get_mut(name) {
+                    f(child)
+                }
+            }
+            MatchObject(name, pattern) => {
+                if create.is_some() && tag.is_compound() && tag.get(name).is_none() {
+                    let _ = tag.insert(name, pattern.clone());
+                }
+
+                if let Some(child) = tag.get_mut(name) {
+                    if matches(pattern, child) {
+                        f(child)
+                    }
+                }
+            }
+            AllElements => if let Tag::List(elems) = tag {
+                if let Some(created) = create {
+                    if elems.is_empty() {
+                        elems.push(created);
+                    }
+                }
+                elems.iter_mut().for_each(f)
+            },
+            Index(idx) => if let Tag::List(elems) = tag {
+                if let Some(i) = resolve_index(*idx, elems.len()) {
+                    f(&mut elems[i])
+                }
+            },
+            MatchElement(pattern) => if let Tag::List(elems) = tag {
+                if create.is_some() && !elems.iter().any(|elem| matches(pattern, elem)) && list_accepts(elems, pattern) {
+                    elems.push(pattern.clone());
+                }
+
+                elems.iter_mut().filter(|elem| matches(pattern, elem)).for_each(f)
+            },
+        }
+    }
+
+    fn preferred_parent(&self) -> Tag {
+        use NbtPathNode::*;
+        match self {
+            MatchRootObject(_) | Named(_) | MatchObject(_, _) => Tag::Compound(Vec::new()),
+            AllElements | Index(_) | MatchElement(_) => Tag::List(Vec::new()),
+        }
+    }
+
+    fn set(&self, parent: &mut Tag, value: &Tag) -> usize {
+        use NbtPathNode::*;
+        match self {
+            MatchRootObject(_) => 0,
+            Named(name) => {
+                if !parent.is_compound() || parent.get(name) == Some(value) {
+                    0
+                } else {
+                    let _ = parent.insert(name, value.clone());
+                    1
+                }
+            }
+            MatchObject(name, pattern) => match parent.get_mut(name) {
+                Some(child) if matches(pattern, child) && child != value => {
+                    *child = value.clone();
+                    1
+                }
+                _ => 0,
+            },
+            AllElements => match parent {
+                Tag::List(elems) if elems.is_empty() => {
+                    elems.push(value.clone());
+                    1
+                }
+                Tag::List(elems) if list_accepts(elems, value) => {
+                    set_all(elems.iter_mut(), value)
+                }
+                _ => set_array_elements(parent, value, None),
+            },
+            Index(idx) => match parent {
+                Tag::List(elems) if list_accepts(elems, value) => {
+                    set_all(resolve_index(*idx, elems.len()).map(|i| &mut elems[i]).into_iter(), value)
+                }
+                Tag::List(_) => 0,
+                _ => set_array_elements(parent, value, Some(*idx)),
+            },
+            MatchElement(pattern) => match parent {
+                Tag::List(elems) if list_accepts(elems, value) => {
+                    set_all(elems.iter_mut().filter(|elem| matches(pattern, elem)), value)
+                }
+                _ => 0,
+            },
+        }
+    }
+
+    fn remove(&self, parent: &mut Tag) -> usize {
+        use NbtPathNode::*;
+        match self {
+            MatchRootObject(_) => 0,
+            Named(name) => parent.remove(name).map(move |_| 1).unwrap_or(0),
+            MatchObject(name, pattern) => {
+                if parent.get(name).map(|child| matches(pattern, child)).unwrap_or(false) {
+                    parent.remove(name);
+                    1
+                } else {
+                    0
+                }
+            }
+            AllElements => {
+                let count = collection_len(parent);
+                match parent {
+                    Tag::List(elems) => elems.clear(),
+                    Tag::ByteArray(elems) => elems.clear(),
+                    Tag::IntArray(elems) => elems.clear(),
+                    Tag::LongArray(elems) => elems.clear(),
+                    _ => {}
+                }
+                count
+            }
+            Index(idx) => match resolve_index(*idx, collection_len(parent)) {
+                Some(i) => {
+                    match parent {
+                        Tag::List(elems) => { elems.remove(i); }
+                        Tag::ByteArray(elems) => { elems.remove(i); }
+                        Tag::IntArray(elems) => { elems.remove(i); }
+                        Tag::LongArray(elems) => { elems.remove(i); }
+                        _ => {}
+                    }
+                    1
+                }
+                None => 0,
+            },
+            MatchElement(pattern) => match parent {
+                Tag::List(elems) => {
+                    let before = elems.len();
+                    elems.retain(|elem| !matches(pattern, elem));
+                    before - elems.len()
+                }
+                _ => 0,
+            },
+        }
+    }
+}
+
+// partial comparison used by {...} filters: compounds only need the keys in the pattern, and
+// every element of a non-empty pattern list must match some element of the target list
+pub fn matches(pattern: &Tag, target: &Tag) -> bool {
+    match (pattern, target) {
+        (Tag::Compound(entries), Tag::Compound(_)) => entries.iter().all(move |entry| {
+            target.get(entry.name.as_str())
+                .map(|child| matches(&entry.payload, child))
+                .unwrap_or(false)
+        }),
+        (Tag::List(expected), Tag::List(actual)) => if expected.is_empty() {
+            actual.is_empty()
+        } else {
+            expected.iter().all(move |e| actual.iter().any(move |a| matches(e, a)))
+        },
+        _ => pattern == target,
+    }
+}
+
+fn resolve_index(idx: i32, len: usize) -> Option<usize> {
+    let idx = if idx < 0 { len as i64 + idx as i64 } else { idx as i64 };
+    if idx >= 0 && (idx as usize) < len {
+        Some(idx as usize)
+    } else {
+        None
+    }
+}
+
+fn list_accepts(elems: &[Tag], value: &Tag) -> bool {
+    elems.first().map(move |first| first.id() == value.id()).unwrap_or(true)
+}
+
+fn collection_len(tag: &Tag) -> usize {
+    match tag {
+        Tag::List(elems) => elems.len(),
+        Tag::ByteArray(elems) => elems.len(),
+        Tag::IntArray(elems) => elems.len(),
+        Tag::LongArray(elems) => elems.len(),
+        _ => 0,
+    }
+}
+
+fn set_all<'a, I>(targets: I, value: &Tag) -> usize where I: Iterator<Item=&'a mut Tag> {
+    let mut count = 0;
+    for target in targets {
+        if target != value {
+            *target = value.clone();
+            count += 1;
+        }
+    }
+    count
+}
+
+fn set_array_elements(parent: &mut Tag, value: &Tag, idx: Option<i32>) -> usize {
+    fn set_in<T: Copy + PartialEq>(elems: &mut [T], value: T, idx: Option<i32>) -> usize {
+        let targets: Vec<usize> = match idx {
+            Some(idx) => resolve_index(idx, elems.len()).into_iter().collect(),
+            None => (0..elems.len()).collect(),
+        };
+
+        let mut count = 0;
+        for i in targets {
+            if elems[i] != value {
+                elems[i] = value;
+                count += 1;
+            }
+        }
+        count
+    }
+
+    match (parent, value) {
+        (Tag::ByteArray(elems), Tag::Byte(v)) => set_in(elems, *v as u8, idx),
+        (Tag::IntArray(elems), Tag::Int(v)) => set_in(elems, *v, idx),
+        (Tag::LongArray(elems), Tag::Long(v)) => set_in(elems, *v, idx),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{vec, borrow::ToOwned};
+
+    fn player() -> NamedTag {
+        super::super::snbt::parse(concat!(
+            "{Pos: [1.0d, 64.0d, -3.5d], UUID: [I; 1, 2, 3, 4], Inventory: [",
+            "{Slot: 0b, id: \"minecraft:diamond_sword\", Count: 1b, tag: {display: {Name: '{\"text\":\"Excalibur\"}'}}},",
+            "{Slot: 1b, id: \"minecraft:stone\", Count: 64b}",
+            "]}"
+        )).expect("valid snbt").with_name("")
+    }
+
+    fn path(text: &str) -> NbtPath {
+        NbtPath::parse(text).expect("valid path")
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(path("Inventory[{Slot:0b}].tag.display.Name").nodes(), &[
+            NbtPathNode::Named("Inventory".to_owned()),
+            NbtPathNode::MatchElement(Tag::Compound(vec![Tag::Byte(0).with_name("Slot")])),
+            NbtPathNode::Named("tag".to_owned()),
+            NbtPathNode::Named("display".to_owned()),
+            NbtPathNode::Named("Name".to_owned()),
+        ][..]);
+
+        assert_eq!(path("{}.\"a b\"{c:1}[][-1]").nodes(), &[
+            NbtPathNode::MatchRootObject(Tag::Compound(vec![])),
+            NbtPathNode::MatchObject("a b".to_owned(), Tag::Compound(vec![Tag::Int(1).with_name("c")])),
+            NbtPathNode::AllElements,
+            NbtPathNode::Index(-1),
+        ][..]);
+
+        assert!(NbtPath::parse("").is_err());
+        assert!(NbtPath::parse("a.").is_err());
+        assert!(NbtPath::parse("a{b:1").is_err());
+        assert!(NbtPath::parse("a[x]").is_err());
+        assert!(NbtPath::parse("a.{b:1}").is_err());
+    }
+
+    #[test]
+    fn test_get() {
+        let player = player();
+        let get = |text: &str| path(text).get(&player).into_iter().map(Cow::into_owned).collect::<Vec<Tag>>();
+
+        assert_eq!(get("Inventory[{Slot:0b}].tag.display.Name"), vec![Tag::String("{\"text\":\"Excalibur\"}".to_owned())]);
+        assert_eq!(get("Pos[1]"), vec![Tag::Double(64.0)]);
+        assert_eq!(get("Pos[-1]"), vec![Tag::Double(-3.5)]);
+        assert_eq!(get("Pos[3]"), vec![]);
+        assert_eq!(get("UUID[2]"), vec![Tag::Int(3)]);
+        assert_eq!(get("Inventory[].Count"), vec![Tag::Byte(1), Tag::Byte(64)]);
+        assert_eq!(get("{Pos:[64.0d]}.UUID[0]"), vec![Tag::Int(1)]);
+        assert_eq!(get("{Pos:[65.0d]}.UUID[0]"), vec![]);
+        assert_eq!(get("Inventory[{id:\"minecraft:stone\"}].Slot"), vec![Tag::Byte(1)]);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut player = player();
+        assert_eq!(path("Inventory[{Slot:1b}].Count").set(&mut player, Tag::Byte(32)), 1);
+        assert_eq!(player.get_path("Inventory").and_then(Tag::as_list).map(move |l| l[1].get_i8("Count")), Some(Some(32)));
+
+        assert_eq!(path("Inventory[].Count").set(&mut player, Tag::Byte(1)), 1);
+        assert_eq!(path("Pos[0]").set(&mut player, Tag::Double(5.0)), 1);
+        assert_eq!(path("Pos[0]").set(&mut player, Tag::Int(5)), 0);
+        assert_eq!(path("UUID[]").set(&mut player, Tag::Int(0)), 4);
+
+        // missing parents are created
+        assert_eq!(path("Inventory[{Slot:1b}].tag.display.Lore[]").set(&mut player, Tag::String("hi".to_owned())), 1);
+        assert_eq!(path("Inventory[{Slot:1b}].tag.display.Lore[0]").get(&player).len(), 1);
+        assert_eq!(path("Attributes[{Name:\"generic.max_health\"}].Base").set(&mut player, Tag::Double(40.0)), 1);
+        assert_eq!(path("Attributes[0].Name").get(&player)[0].as_ref(), &Tag::String("generic.max_health".to_owned()));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut player = player();
+        assert_eq!(path("Inventory[{Slot:0b}].tag").remove(&mut player), 1);
+        assert_eq!(path("Inventory[{Slot:0b}].tag").get(&player).len(), 0);
+        assert_eq!(path("Inventory[{Count:64b}]").remove(&mut player), 1);
+        assert_eq!(path("Inventory[]").get(&player).len(), 1);
+        assert_eq!(path("UUID[-1]").remove(&mut player), 1);
+        assert_eq!(player.get_int_array("UUID"), Some(&[1, 2, 3][..]));
+        assert_eq!(path("Missing.Thing").remove(&mut player), 0);
+        assert_eq!(path("Pos[]").remove(&mut player), 3);
+    }
+}
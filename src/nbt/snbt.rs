@@ -0,0 +1,509 @@
+use super::{NamedTag, Tag};
+use alloc::{string::String, vec::Vec, fmt, format};
+
+// parses the "stringified" nbt syntax used by commands, such as {Count:1b,tag:{Damage:3}}
+pub fn parse(text: &str) -> Result<Tag, SnbtErr> {
+    let source: Vec<char> = text.chars().collect();
+    let mut reader = SnbtReader::new(source.as_slice());
+    let out = reader.read_value()?;
+    reader.skip_whitespace();
+    if reader.can_read() {
+        Err(SnbtErr::TrailingData(reader.at))
+    } else {
+        Ok(out)
+    }
+}
+
+// writes a tag in the compact form vanilla uses, so that parse(stringify(tag)) gives back the same tag
+pub fn stringify(tag: &Tag) -> String {
+    let mut out = String::new();
+    write_tag(tag, &mut out);
+    out
+}
+
+fn write_tag(tag: &Tag, out: &mut String) {
+    match tag {
+        Tag::Byte(v) => out.push_str(format!("{}b", v).as_str()),
+        Tag::Short(v) => out.push_str(format!("{}s", v).as_str()),
+        Tag::Int(v) => out.push_str(format!("{}", v).as_str()),
+        Tag::Long(v) => out.push_str(format!("{}L", v).as_str()),
+        Tag::Float(v) => out.push_str(format!("{}f", v).as_str()),
+        Tag::Double(v) => out.push_str(format!("{}d", v).as_str()),
+        Tag::ByteArray(v) => write_array('B', v.iter().map(move |b| format!("{}b", *b as i8)), out),
+        Tag::String(v) => write_quoted(v, out),
+        Tag::List(v) => {
+            out.push('[');
+            for (idx, elem) in v.iter().enumerate() {
+                if idx != 0 {
+                    out.push(',');
+                }
+                write_tag(elem, out);
+            }
+            out.push(']');
+        }
+        Tag::Compound(v) => {
+            out.push('{');
+            for (idx, elem) in v.iter().enumerate() {
+                if idx != 0 {
+                    out.push(',');
+                }
+                if !elem.name.is_empty() && elem.name.chars().all(is_unquoted_char) {
+                    out.push_str(elem.name.as_str());
+                } else {
+                    write_quoted(elem.name.as_str(), out);
+                }
+                out.push(':');
+                write_tag(&elem.payload, out);
+            }
+            out.push('}');
+        }
+        Tag::IntArray(v) => write_array('I', v.iter().map(move |i| format!("{}", i)), out),
+        Tag::LongArray(v) => write_array('L', v.iter().map(move |l| format!("{}L", l)), out),
+        Tag::End => {}
+    }
+}
+
+fn write_array<I: Iterator<Item=String>>(kind: char, elems: I, out: &mut String) {
+    out.push('[');
+    out.push(kind);
+    out.push(';');
+    for (idx, elem) in elems.enumerate() {
+        if idx != 0 {
+            out.push(',');
+        }
+        out.push_str(elem.as_str());
+    }
+    out.push(']');
+}
+
+fn write_quoted(v: &str, out: &mut String) {
+    out.push('"');
+    for c in v.chars() {
+        if c == '"' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+}
+
+pub enum SnbtErr {
+    Eof,
+    Unexpected(usize, char),
+    EmptyKey(usize),
+    MixedList(usize),
+    BadArrayElement(usize),
+    TrailingData(usize),
+}
+
+impl fmt::Display for SnbtErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use SnbtErr::*;
+        match self {
+            Eof => f.write_str("snbt: unexpected end of input"),
+            Unexpected(at, c) => f.write_fmt(format_args!("snbt: unexpected {:?} at {}", c, at)),
+            EmptyKey(at) => f.write_fmt(format_args!("snbt: expected key at {}", at)),
+            MixedList(at) => f.write_fmt(format_args!("snbt: list contains tags of different types at {}", at)),
+            BadArrayElement(at) => f.write_fmt(format_args!("snbt: invalid array element at {}", at)),
+            TrailingData(at) => f.write_fmt(format_args!("snbt: unexpected trailing data at {}", at)),
+        }
+    }
+}
+
+impl fmt::Debug for SnbtErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <dyn fmt::Display>::fmt(self, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SnbtErr {}
+
+pub(crate) struct SnbtReader<'a> {
+    source: &'a [char],
+    pub(crate) at: usize,
+}
+
+impl<'a> SnbtReader<'a> {
+    pub(crate) fn new(source: &'a [char]) -> Self {
+        Self { source, at: 0 }
+    }
+
+    pub(crate) fn can_read(&self) -> bool {
+        self.at < self.source.len()
+    }
+
+    pub(crate) fn peek(&self) -> Option<char> {
+        self.source.get(self.at).copied()
+    }
+
+    pub(crate) fn next(&mut self) -> Result<char, SnbtErr> {
+        let out = self.peek().ok_or(SnbtErr::Eof)?;
+        self.at += 1;
+        Ok(out)
+    }
+
+    pub(crate) fn expect(&mut self, expected: char) -> Result<(), SnbtErr> {
+        self.skip_whitespace();
+        let at = self.at;
+        match self.next()? {
+            c if c == expected => Ok(()),
+            other => Err(SnbtErr::Unexpected(at, other)),
+        }
+    }
+
+    pub(crate) fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.at += 1;
+        }
+    }
+
+    pub(crate) fn read_value(&mut self) -> Result<Tag, SnbtErr> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.read_compound(),
+            Some('[') => self.read_list_or_array(),
+            Some(_) => self.read_primitive(),
+            None => Err(SnbtErr::Eof),
+        }
+    }
+
+    pub(crate) fn read_compound(&mut self) -> Result<Tag, SnbtErr> {
+        self.expect('{')?;
+        let mut entries: Vec<NamedTag> = Vec::new();
+        self.skip_whitespace();
+        while self.peek() != Some('}') {
+            let key_at = self.at;
+            let key = self.read_string()?;
+            if key.is_empty() {
+                return Err(SnbtErr::EmptyKey(key_at));
+            }
+            self.expect(':')?;
+            let value = self.read_value()?;
+            if let Some(existing) = entries.iter_mut().find(|elem| elem.name == key) {
+                existing.payload = value;
+            } else {
+                entries.push(value.with_name(key.as_str()));
+            }
+
+            if !self.has_element_separator() {
+                break;
+            }
+        }
+
+        self.expect('}')?;
+        Ok(Tag::Compound(entries))
+    }
+
+    fn read_list_or_array(&mut self) -> Result<Tag, SnbtErr> {
+        let is_array = self.source.get(self.at + 2) == Some(&';') &&
+            self.source.get(self.at + 1).map(move |c| !(*c == '"' || *c == '\'')).unwrap_or(false);
+
+        if is_array {
+            self.read_array()
+        } else {
+            self.read_list()
+        }
+    }
+
+    fn read_list(&mut self) -> Result<Tag, SnbtErr> {
+        self.expect('[')?;
+        let mut out: Vec<Tag> = Vec::new();
+        self.skip_whitespace();
+        while self.peek() != Some(']') {
+            let elem_at = self.at;
+            let elem = self.read_value()?;
+            if let Some(first) = out.first() {
+                if first.id() != elem.id() {
+                    return Err(SnbtErr::MixedList(elem_at));
+                }
+            }
+            out.push(elem);
+
+            if !self.has_element_separator() {
+                break;
+            }
+        }
+
+        self.expect(']')?;
+        Ok(Tag::List(out))
+    }
+
+    fn read_array(&mut self) -> Result<Tag, SnbtErr> {
+        self.expect('[')?;
+        let kind_at = self.at;
+        let kind = self.next()?;
+        self.expect(';')?;
+        let mut elems = Vec::new();
+        self.skip_whitespace();
+        while self.peek() != Some(']') {
+            let elem_at = self.at;
+            elems.push((elem_at, self.read_value()?));
+            if !self.has_element_separator() {
+                break;
+            }
+        }
+        self.expect(']')?;
+
+        match kind {
+            'B' => elems.into_iter()
+                .map(move |(at, elem)| elem.as_i8().map(move |v| v as u8).ok_or(SnbtErr::BadArrayElement(at)))
+                .collect::<Result<Vec<u8>, SnbtErr>>()
+                .map(Tag::ByteArray),
+            'I' => elems.into_iter()
+                .map(move |(at, elem)| elem.as_i32().ok_or(SnbtErr::BadArrayElement(at)))
+                .collect::<Result<Vec<i32>, SnbtErr>>()
+                .map(Tag::IntArray),
+            'L' => elems.into_iter()
+                .map(move |(at, elem)| match elem {
+                    // vanilla accepts any integral tag inside of a long array
+                    Tag::Byte(v) => Ok(v as i64),
+                    Tag::Short(v) => Ok(v as i64),
+                    Tag::Int(v) => Ok(v as i64),
+                    Tag::Long(v) => Ok(v),
+                    _ => Err(SnbtErr::BadArrayElement(at)),
+                })
+                .collect::<Result<Vec<i64>, SnbtErr>>()
+                .map(Tag::LongArray),
+            other => Err(SnbtErr::Unexpected(kind_at, other)),
+        }
+    }
+
+    fn has_element_separator(&mut self) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(',') {
+            self.at += 1;
+            self.skip_whitespace();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn read_primitive(&mut self) -> Result<Tag, SnbtErr> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"') | Some('\'') => Ok(Tag::String(self.read_quoted_string()?)),
+            _ => {
+                let at = self.at;
+                let raw = self.read_unquoted_string();
+                if raw.is_empty() {
+                    match self.peek() {
+                        Some(c) => Err(SnbtErr::Unexpected(at, c)),
+                        None => Err(SnbtErr::Eof),
+                    }
+                } else {
+                    Ok(type_unquoted(raw.as_str()))
+                }
+            }
+        }
+    }
+
+    pub(crate) fn read_string(&mut self) -> Result<String, SnbtErr> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"') | Some('\'') => self.read_quoted_string(),
+            _ => Ok(self.read_unquoted_string()),
+        }
+    }
+
+    pub(crate) fn read_quoted_string(&mut self) -> Result<String, SnbtErr> {
+        let quote = self.next()?;
+        let mut out = String::new();
+        loop {
+            let at = self.at;
+            match self.next()? {
+                '\\' => match self.next()? {
+                    c if c == quote || c == '\\' => out.push(c),
+                    other => return Err(SnbtErr::Unexpected(at + 1, other)),
+                },
+                c if c == quote => return Ok(out),
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn read_unquoted_string(&mut self) -> String {
+        let mut out = String::new();
+        while let Some(c) = self.peek() {
+            if !is_unquoted_char(c) {
+                break;
+            }
+            out.push(c);
+            self.at += 1;
+        }
+
+        out
+    }
+}
+
+pub(crate) fn is_unquoted_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '+'
+}
+
+// decides the type of an unquoted value the same way vanilla does, falling back to a string
+fn type_unquoted(raw: &str) -> Tag {
+    if let Some((body, suffix)) = split_suffix(raw) {
+        let parsed = match suffix.to_ascii_lowercase() {
+            'b' if is_integer(body) => body.parse().ok().map(Tag::Byte),
+            's' if is_integer(body) => body.parse().ok().map(Tag::Short),
+            'l' if is_integer(body) => body.parse().ok().map(Tag::Long),
+            'f' if is_float(body, true) => body.parse().ok().map(Tag::Float),
+            'd' if is_float(body, true) => body.parse().ok().map(Tag::Double),
+            _ => None,
+        };
+
+        if let Some(parsed) = parsed {
+            return parsed;
+        }
+    }
+
+    if is_integer(raw) {
+        if let Ok(v) = raw.parse() {
+            return Tag::Int(v);
+        }
+    }
+
+    if is_float(raw, false) {
+        if let Ok(v) = raw.parse() {
+            return Tag::Double(v);
+        }
+    }
+
+    if raw.eq_ignore_ascii_case("true") {
+        Tag::Byte(1)
+    } else if raw.eq_ignore_ascii_case("false") {
+        Tag::Byte(0)
+    } else {
+        Tag::String(raw.into())
+    }
+}
+
+fn split_suffix(raw: &str) -> Option<(&str, char)> {
+    let suffix = raw.chars().last()?;
+    if suffix.is_ascii_alphabetic() {
+        Some((&raw[..raw.len() - 1], suffix))
+    } else {
+        None
+    }
+}
+
+fn strip_sign(raw: &str) -> &str {
+    raw.strip_prefix('-').or_else(|| raw.strip_prefix('+')).unwrap_or(raw)
+}
+
+// [-+]?(?:0|[1-9][0-9]*)
+fn is_integer(raw: &str) -> bool {
+    let digits = strip_sign(raw);
+    match digits.as_bytes() {
+        [] => false,
+        [b'0'] => true,
+        [b'0', ..] => false,
+        other => other.iter().all(u8::is_ascii_digit),
+    }
+}
+
+// [-+]?(?:[0-9]+[.]|[0-9]*[.][0-9]+)(?:e[-+]?[0-9]+)?, where the dot is optional when there is a suffix
+fn is_float(raw: &str, dot_optional: bool) -> bool {
+    let raw = strip_sign(raw);
+    let (mantissa, exponent) = match raw.find(['e', 'E']) {
+        Some(idx) => (&raw[..idx], Some(&raw[idx + 1..])),
+        None => (raw, None),
+    };
+
+    if let Some(exponent) = exponent {
+        let exponent = strip_sign(exponent);
+        if exponent.is_empty() || !exponent.bytes().all(|b| b.is_ascii_digit()) {
+            return false;
+        }
+    }
+
+    let (whole, fraction) = match mantissa.find('.') {
+        Some(idx) => (&mantissa[..idx], Some(&mantissa[idx + 1..])),
+        None => (mantissa, None),
+    };
+
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    match fraction {
+        Some(fraction) => all_digits(whole) && all_digits(fraction) && !(whole.is_empty() && fraction.is_empty()),
+        None => dot_optional && !whole.is_empty() && all_digits(whole),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{vec, borrow::ToOwned};
+
+    #[test]
+    fn test_parse_compound() {
+        let parsed = parse("{Count: 1b, id: \"minecraft:stone\", tag: {Damage: 3, display: {Name: 'it\\'s'}}, Pos: [1.0d, 2.5, -3.], Motion: [], flag: true}")
+            .expect("valid snbt");
+
+        assert_eq!(parsed, Tag::Compound(vec![
+            Tag::Byte(1).with_name("Count"),
+            Tag::String("minecraft:stone".to_owned()).with_name("id"),
+            Tag::Compound(vec![
+                Tag::Int(3).with_name("Damage"),
+                Tag::Compound(vec![
+                    Tag::String("it's".to_owned()).with_name("Name"),
+                ]).with_name("display"),
+            ]).with_name("tag"),
+            Tag::List(vec![Tag::Double(1.0), Tag::Double(2.5), Tag::Double(-3.0)]).with_name("Pos"),
+            Tag::List(vec![]).with_name("Motion"),
+            Tag::Byte(1).with_name("flag"),
+        ]));
+    }
+
+    #[test]
+    fn test_parse_typed_numbers() {
+        assert_eq!(parse("12s").expect("short"), Tag::Short(12));
+        assert_eq!(parse("-12L").expect("long"), Tag::Long(-12));
+        assert_eq!(parse("1f").expect("float"), Tag::Float(1.0));
+        assert_eq!(parse(".5").expect("double"), Tag::Double(0.5));
+        assert_eq!(parse("1e3").expect("string"), Tag::String("1e3".to_owned()));
+        assert_eq!(parse("300b").expect("string"), Tag::String("300b".to_owned()));
+        assert_eq!(parse("01").expect("string"), Tag::String("01".to_owned()));
+        assert_eq!(parse("2147483648").expect("string"), Tag::String("2147483648".to_owned()));
+    }
+
+    #[test]
+    fn test_parse_arrays() {
+        assert_eq!(parse("[B; 1b, -1b]").expect("byte array"), Tag::ByteArray(vec![1, 255]));
+        assert_eq!(parse("[I;1,2,3]").expect("int array"), Tag::IntArray(vec![1, 2, 3]));
+        assert_eq!(parse("[L;1l,2]").expect("long array"), Tag::LongArray(vec![1, 2]));
+        assert!(parse("[I;1b]").is_err());
+        assert!(parse("[1b, 2s]").is_err());
+    }
+
+    #[test]
+    fn test_stringify_round_trip() {
+        let tag = Tag::Compound(vec![
+            Tag::Byte(-1).with_name("Count"),
+            Tag::String("say \"hi\" \\o/".to_owned()).with_name("id"),
+            Tag::Float(1.5).with_name("needs quotes"),
+            Tag::List(vec![Tag::Double(1.0), Tag::Double(-2.25)]).with_name("Pos"),
+            Tag::Long(i64::MIN).with_name("l"),
+            Tag::Short(7).with_name("s"),
+            Tag::ByteArray(vec![1, 255]).with_name("ba"),
+            Tag::IntArray(vec![-1, 2]).with_name("ia"),
+            Tag::LongArray(vec![3]).with_name("la"),
+            Tag::Compound(vec![]).with_name("empty"),
+        ]);
+
+        let text = stringify(&tag);
+        assert_eq!(text.as_str(), "{Count:-1b,id:\"say \\\"hi\\\" \\\\o/\",\"needs quotes\":1.5f,Pos:[1d,-2.25d],l:-9223372036854775808L,s:7s,ba:[B;1b,-1b],ia:[I;-1,2],la:[L;3L],empty:{}}");
+        assert_eq!(parse(text.as_str()).expect("should parse"), tag);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("{a:1").is_err());
+        assert!(parse("{:1}").is_err());
+        assert!(parse("{a:1} b").is_err());
+        assert!(parse("\"unterminated").is_err());
+    }
+}
@@ -0,0 +1,223 @@
+use crate::uuid::UUID4;
+use serde::{Deserialize, Serialize, Serializer};
+use alloc::{string::{String, ToString}, fmt};
+
+#[cfg(feature = "signatures")]
+mod key;
+#[cfg(feature = "signatures")]
+pub use key::*;
+
+pub const TEXTURES_PROPERTY_NAME: &str = "textures";
+
+// the decoded value of a profile's textures property, which is base64 encoded json
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ProfileTexturesSpec {
+    pub timestamp: i64,
+    #[serde(rename = "profileId", serialize_with = "serialize_undashed_uuid")]
+    pub profile_id: UUID4,
+    #[serde(rename = "profileName")]
+    pub profile_name: String,
+    #[serde(rename = "signatureRequired", default, skip_serializing_if = "Option::is_none")]
+    pub signature_required: Option<bool>,
+    pub textures: ProfileTextureSetSpec,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ProfileTextureSetSpec {
+    #[serde(rename = "SKIN", default, skip_serializing_if = "Option::is_none")]
+    pub skin: Option<ProfileSkinSpec>,
+    #[serde(rename = "CAPE", default, skip_serializing_if = "Option::is_none")]
+    pub cape: Option<ProfileTextureSpec>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ProfileSkinSpec {
+    pub url: String,
+    // only sent for slim skins
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ProfileSkinMetadataSpec>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ProfileSkinMetadataSpec {
+    pub model: SkinModel,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SkinModel {
+    #[serde(rename = "default")]
+    Classic,
+    #[serde(rename = "slim")]
+    Slim,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ProfileTextureSpec {
+    pub url: String,
+}
+
+impl ProfileSkinSpec {
+    pub fn model(&self) -> SkinModel {
+        self.metadata.as_ref().map(move |metadata| metadata.model).unwrap_or(SkinModel::Classic)
+    }
+}
+
+#[derive(Debug)]
+pub enum ProfileTexturesErr {
+    NotTextures(String),
+    BadBase64(base64::DecodeError),
+    BadJson(serde_json::Error),
+}
+
+impl fmt::Display for ProfileTexturesErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ProfileTexturesErr::*;
+        match self {
+            NotTextures(name) => f.write_fmt(format_args!(
+                "expected a {:?} property, got {:?}",
+                TEXTURES_PROPERTY_NAME, name
+            )),
+            BadBase64(err) => f.write_fmt(format_args!("textures property is not valid base64: {}", err)),
+            BadJson(err) => f.write_fmt(format_args!("textures property is not valid json: {}", err)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ProfileTexturesErr {}
+
+impl ProfileTexturesSpec {
+    pub fn decode(value: &str) -> Result<Self, ProfileTexturesErr> {
+        let json = base64::decode(value).map_err(ProfileTexturesErr::BadBase64)?;
+        serde_json::from_slice(json.as_slice()).map_err(ProfileTexturesErr::BadJson)
+    }
+
+    pub fn decode_property(name: &str, value: &str) -> Result<Self, ProfileTexturesErr> {
+        if name != TEXTURES_PROPERTY_NAME {
+            return Err(ProfileTexturesErr::NotTextures(name.to_string()));
+        }
+
+        Self::decode(value)
+    }
+
+    // a new value for the property, which has to be signed again (by whoever owns the key) if it's going to be trusted
+    pub fn encode(&self) -> String {
+        base64::encode(serde_json::to_vec(self).expect("textures are valid json"))
+    }
+}
+
+#[cfg(feature = "signatures")]
+impl YggdrasilPublicKey {
+    // checks a property's signature (base64, as sent in the packet) against its value (still base64 encoded)
+    pub fn verify_property(&self, value: &str, signature: &str) -> bool {
+        match base64::decode(signature) {
+            Ok(signature) => self.verify(value.as_bytes(), signature.as_slice()),
+            Err(_) => false,
+        }
+    }
+}
+
+// the session server sends these without dashes
+fn serialize_undashed_uuid<S>(id: &UUID4, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(id.hex().replace('-', "").as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_VALUE: &str = "eyJ0aW1lc3RhbXAiOjE2MDUxMzg1NDI2NTEsInByb2ZpbGVJZCI6IjQ1NjZlNjlmYzkwNzQ4ZWU4ZDcxZDdiYTVhYTAwZDIwIiwicHJvZmlsZU5hbWUiOiJUaGlua29mZGVhdGgiLCJzaWduYXR1cmVSZXF1aXJlZCI6dHJ1ZSwidGV4dHVyZXMiOnsiU0tJTiI6eyJ1cmwiOiJodHRwOi8vdGV4dHVyZXMubWluZWNyYWZ0Lm5ldC90ZXh0dXJlLzc0ZDFlMDhiMGJiN2U5ZjU5MGFmMjc3NTgxMjViYmVkMTc3OGFjNmNlZjcyOWFlZGZjYjk2MTNlOTkxMWFlNzUiLCJtZXRhZGF0YSI6eyJtb2RlbCI6InNsaW0ifX0sIkNBUEUiOnsidXJsIjoiaHR0cDovL3RleHR1cmVzLm1pbmVjcmFmdC5uZXQvdGV4dHVyZS85NTNjYWM4Yjc3OWZlNDEzODNlNjc1ZWUyYjg2MDcxYTcxNjU4ZjIxODBmNTZmYmNlOGFhMzE1ZWE3MGUyZWQ2In19fQ==";
+
+    // signed with the private half of testdata/profile_key.der
+    #[cfg(feature = "signatures")]
+    const TEST_SIGNATURE: &str = "WTbtqQabZYvoXHSsIMR0ksqi21QOXB2ax9c2iXOl6KBD2xj0ByBDwsyOx98ssHww23OHWw37MekhfAbB3BL3zaMNezs60okH+vNeZ5lx4EHwBWuSFVi1xD4e/QyeSe4He/4s2u0N0byPlWzYMy8ZyqHd74L/5K4dwPoXd50a1VY=";
+
+    // signed with the private half of testdata/profile_key_4096.der
+    #[cfg(feature = "signatures")]
+    const TEST_SIGNATURE_4096: &str = "mt6+tL9r5LCOuXg+MDFerdCIWMS9YfxqfGWcqVwLMJlGexFIQuIKdZclVW0OjiT7B04YIK1R1MZALn3IsBc9VSCIeI4tjTSquvLVhuRXnl0BZcmoU7gA/yXGnIN81mfDDaAsRTFUAUQ8/fysT40sYy5AJe3z+NxTMQjOLCem846v/CISOBI5WcpMNDFtoP7JxC6rGDeeB3vC0Ms2la5rbyhsGBwOrsMw1rqmwuib4NWjXoyZWaWQRYUQMiZcMNIC9VA8Hsof/LoOdflFgKuLafmaqYxBwPGGjm7x4/yt9ZBQxx5aBaG/+qrv4BbmsUuPQv5kxPChr1lwU43p0Fwp2m7nS1QlP+yflRjcAIc6cM9UEHOb8D0+SGBzvGg7LwXCANw9AC1YGuEch4XnGTbxQzAzaGgn+GaIVVU1TS1bZizNPDf3/FijI/V/KgwgHdKkwL4JYIpNJa7C4atIiVlk/9kdOWTEUj+ttKIFThL/l94951Aiwq8mQk7UEz4EFwS+zgv7KRulbfKCRDyDXCXgZ4+hx6dmevYLkNmuHpD62d+KF/bw3Q4G+Cipbt8vTs5pGSOBPs6vPJcs/vjBwc04DMfiLlnp8SuSWeSyTzuIOUT4H8HTZwYq4yyV0JcupEFoSbiSCPQgv1RFpWMM40gIpsyEdBBowWl8U2SSkKgv5WE=";
+
+    #[cfg(feature = "signatures")]
+    fn test_key() -> YggdrasilPublicKey {
+        YggdrasilPublicKey::from_der(include_bytes!("testdata/profile_key.der")).expect("valid key")
+    }
+
+    #[test]
+    fn test_decode_textures() {
+        let textures = ProfileTexturesSpec::decode_property(TEXTURES_PROPERTY_NAME, TEST_VALUE).expect("valid textures");
+        assert_eq!(textures.timestamp, 1605138542651);
+        assert_eq!(textures.profile_id, UUID4::parse("4566e69f-c907-48ee-8d71-d7ba5aa00d20").expect("valid uuid"));
+        assert_eq!(textures.profile_name.as_str(), "Thinkofdeath");
+        assert_eq!(textures.signature_required, Some(true));
+
+        let skin = textures.textures.skin.as_ref().expect("has a skin");
+        assert_eq!(skin.model(), SkinModel::Slim);
+        assert!(skin.url.ends_with("/74d1e08b0bb7e9f590af27758125bbed1778ac6cef729aedfcb9613e9911ae75"));
+        let cape = textures.textures.cape.as_ref().expect("has a cape");
+        assert!(cape.url.ends_with("/953cac8b779fe41383e675ee2b86071a71658f2180f56fbce8aa315ea70e2ed6"));
+
+        // the json is written back in the same order, so this comes out byte for byte
+        assert_eq!(textures.encode().as_str(), TEST_VALUE);
+
+        assert!(matches!(ProfileTexturesSpec::decode_property("skin", TEST_VALUE), Err(ProfileTexturesErr::NotTextures(_))));
+        assert!(matches!(ProfileTexturesSpec::decode("not base64!"), Err(ProfileTexturesErr::BadBase64(_))));
+        assert!(matches!(ProfileTexturesSpec::decode("e30="), Err(ProfileTexturesErr::BadJson(_))));
+    }
+
+    #[test]
+    fn test_decode_textures_bad_profile_id() {
+        // a multi-byte char in the id is an error, not a panic
+        let json = r#"{"timestamp":1605138542651,"profileId":"aaaaaaaéaaaaaaaaaaaaaaaaaaaaaa","profileName":"a","textures":{}}"#;
+        let value = base64::encode(json);
+        assert!(matches!(ProfileTexturesSpec::decode(value.as_str()), Err(ProfileTexturesErr::BadJson(_))));
+        assert!(matches!(ProfileTexturesSpec::decode_property(TEXTURES_PROPERTY_NAME, value.as_str()), Err(ProfileTexturesErr::BadJson(_))));
+
+        // the same shape with a real id decodes fine
+        let value = base64::encode(json.replace("aaaaaaaéaaaaaaaaaaaaaaaaaaaaaa", "4566e69fc90748ee8d71d7ba5aa00d20"));
+        ProfileTexturesSpec::decode(value.as_str()).expect("valid textures");
+    }
+
+    #[test]
+    fn test_classic_skin() {
+        let mut textures = ProfileTexturesSpec::decode(TEST_VALUE).expect("valid textures");
+        textures.textures.skin.as_mut().expect("has a skin").metadata = None;
+        textures.textures.cape = None;
+
+        let decoded = ProfileTexturesSpec::decode(textures.encode().as_str()).expect("valid textures");
+        assert_eq!(decoded.textures.skin.as_ref().expect("has a skin").model(), SkinModel::Classic);
+        assert_eq!(decoded, textures);
+    }
+
+    #[cfg(feature = "signatures")]
+    #[test]
+    fn test_verify_textures() {
+        let key = test_key();
+        assert!(key.verify_property(TEST_VALUE, TEST_SIGNATURE));
+
+        // swapping in another skin breaks the signature
+        let mut forged = ProfileTexturesSpec::decode(TEST_VALUE).expect("valid textures");
+        forged.textures.skin.as_mut().expect("has a skin").url = "http://example.com/skin.png".to_string();
+        assert!(!key.verify_property(forged.encode().as_str(), TEST_SIGNATURE));
+
+        let mut signature = base64::decode(TEST_SIGNATURE).expect("valid base64");
+        signature[64] ^= 1;
+        assert!(!key.verify_property(TEST_VALUE, base64::encode(&signature).as_str()));
+        assert!(!key.verify_property(TEST_VALUE, "not base64!"));
+        assert!(!key.verify_property(TEST_VALUE, "AAAA"));
+    }
+
+    #[cfg(feature = "signatures")]
+    #[test]
+    fn test_verify_textures_4096() {
+        let key = YggdrasilPublicKey::from_der(include_bytes!("testdata/profile_key_4096.der")).expect("valid key");
+        assert!(key.verify_property(TEST_VALUE, TEST_SIGNATURE_4096));
+        assert!(!key.verify_property(TEST_VALUE, TEST_SIGNATURE));
+
+        let mut signature = base64::decode(TEST_SIGNATURE_4096).expect("valid base64");
+        signature[256] ^= 1;
+        assert!(!key.verify_property(TEST_VALUE, base64::encode(&signature).as_str()));
+        assert!(!test_key().verify_property(TEST_VALUE, TEST_SIGNATURE_4096));
+    }
+}
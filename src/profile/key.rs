@@ -0,0 +1,85 @@
+// checks the PKCS#1 v1.5 sha1 signatures (SHA1withRSA) the session server puts on profile properties, using the rsa and
+// sha1 crates
+use alloc::{string::String, vec::Vec};
+use rsa::{BigUint, Pkcs1v15Sign, RsaPublicKey, traits::PublicKeyParts};
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs8::DecodePublicKey;
+use sha1::{Digest, Sha1};
+
+// the key the session server signs profile properties with. vanilla ships it as yggdrasil_session_pubkey.der
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct YggdrasilPublicKey {
+    key: RsaPublicKey,
+    modulus: Vec<u8>,
+    exponent: Vec<u8>,
+}
+
+impl YggdrasilPublicKey {
+    // both big endian
+    pub fn from_components(modulus: &[u8], exponent: &[u8]) -> Option<Self> {
+        RsaPublicKey::new(BigUint::from_bytes_be(modulus), BigUint::from_bytes_be(exponent))
+            .ok()
+            .map(Self::from_key)
+    }
+
+    // either a SubjectPublicKeyInfo (what java and openssl export) or a bare PKCS#1 RSAPublicKey
+    pub fn from_der(data: &[u8]) -> Option<Self> {
+        RsaPublicKey::from_public_key_der(data)
+            .ok()
+            .or_else(move || RsaPublicKey::from_pkcs1_der(data).ok())
+            .map(Self::from_key)
+    }
+
+    pub fn from_pem(data: &str) -> Option<Self> {
+        let body: String = data
+            .lines()
+            .map(move |line| line.trim())
+            .filter(move |line| !line.starts_with("-----"))
+            .collect();
+        base64::decode(body).ok().and_then(move |der| Self::from_der(der.as_slice()))
+    }
+
+    fn from_key(key: RsaPublicKey) -> Self {
+        let modulus = key.n().to_bytes_be();
+        let exponent = key.e().to_bytes_be();
+        Self { key, modulus, exponent }
+    }
+
+    pub fn modulus(&self) -> &[u8] {
+        self.modulus.as_slice()
+    }
+
+    pub fn exponent(&self) -> &[u8] {
+        self.exponent.as_slice()
+    }
+
+    pub fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        let hash = Sha1::digest(data);
+        self.key.verify(Pkcs1v15Sign::new::<Sha1>(), hash.as_slice(), signature).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_der_key() {
+        let key = YggdrasilPublicKey::from_der(include_bytes!("../testdata/profile_key.der")).expect("valid key");
+        assert_eq!(key.modulus().len(), 128);
+        assert_eq!(key.exponent(), &[0x01, 0x00, 0x01]);
+
+        // the same key without the SubjectPublicKeyInfo wrapper
+        let der = include_bytes!("../testdata/profile_key.der");
+        let pkcs1 = &der[der.len() - 140..];
+        assert_eq!(YggdrasilPublicKey::from_der(pkcs1), Some(key.clone()));
+        assert_eq!(YggdrasilPublicKey::from_components(key.modulus(), key.exponent()), Some(key));
+
+        // the same size as the real session server key
+        let large = YggdrasilPublicKey::from_der(include_bytes!("../testdata/profile_key_4096.der")).expect("valid key");
+        assert_eq!(large.modulus().len(), 512);
+
+        assert_eq!(YggdrasilPublicKey::from_der(&der[..40]), None);
+        assert_eq!(YggdrasilPublicKey::from_components(&[0, 0], &[3]), None);
+    }
+}
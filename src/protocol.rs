@@ -1,4 +1,4 @@
-use crate::{Deserialize, DeserializeErr, Serialize, Serializer, SerializeResult};
+use crate::{Deserialize, DeserializeErr, Serialize, Serializer, SerializeResult, SizeCounter};
 use alloc::{string::String, fmt, vec::Vec, borrow::ToOwned};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -49,6 +49,10 @@ impl Serialize for Id {
     fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
         to.serialize_other(&crate::types::VarInt(self.id))
     }
+
+    fn serialized_size(&self) -> usize {
+        crate::types::VarInt(self.id).serialized_size()
+    }
 }
 
 impl From<(i32, State, PacketDirection)> for Id {
@@ -114,6 +118,13 @@ pub trait HasPacketId {
 pub trait HasPacketBody {
 
     fn mc_serialize_body<S>(&self, to: &mut S) -> SerializeResult where S: Serializer;
+
+    // like Serialize::serialized_size, the macro generated packets override this
+    fn serialized_body_size(&self) -> usize {
+        let mut counter = SizeCounter::default();
+        let _ = self.mc_serialize_body(&mut counter);
+        counter.size()
+    }
 }
 
 pub trait RawPacket<'a>: HasPacketId + Sized {
@@ -127,7 +138,13 @@ pub trait RawPacket<'a>: HasPacketId + Sized {
     fn deserialize(&self) -> Result<Self::Packet, PacketErr>;
 }
 
-pub trait Packet: HasPacketId + HasPacketBody + Sized {}
+pub trait Packet: HasPacketId + HasPacketBody + Sized {
+
+    // the id and the body, which is what the length at the start of a frame counts
+    fn serialized_size(&self) -> usize {
+        self.id().serialized_size() + self.serialized_body_size()
+    }
+}
 
 pub enum PacketErr {
     UnknownId(Id),
@@ -186,6 +203,10 @@ macro_rules! proto_struct {
             fn mc_serialize<S: Serializer>(&self, _: &mut S) -> SerializeResult {
                 Ok(())
             }
+
+            fn serialized_size(&self) -> usize {
+                0
+            }
         }
 
         impl Deserialize for $bodyt {
@@ -218,6 +239,10 @@ macro_rules! proto_struct {
                 )+
                 Ok(())
             }
+
+            fn serialized_size(&self) -> usize {
+                0 $(+ self.$fname.serialized_size())+
+            }
         }
 
         impl$(<$($g),*>)? Deserialize for $bodyt$(<$($g),*> where $($g: Deserialize + alloc::fmt::Debug + Clone + PartialEq),*)? {
@@ -307,6 +332,13 @@ macro_rules! define_protocol {
                     $($nam(body) => to.serialize_other(body)),+
                 }
             }
+
+            fn serialized_body_size(&self) -> usize {
+                use self::$packett::*;
+                match self {
+                    $($nam(body) => crate::Serialize::serialized_size(body)),+
+                }
+            }
         }
 
         impl crate::protocol::Packet for $packett {}
@@ -496,6 +528,7 @@ macro_rules! proto_enum_with_type {
     ($typ: ty, $typname: ident, $(($bval: literal, $nam: ident $(($bod: ty))?)),*) => {
         $crate::as_item! {
             #[derive(PartialEq, Clone, Debug)]
+            #[allow(clippy::large_enum_variant)]
             pub enum $typname {
                 $($nam $(($bod))?),*
             }
@@ -509,6 +542,10 @@ macro_rules! proto_enum_with_type {
                 to.serialize_other(&id_to_serialize)?;
                 self.serialize_body(to)
             }
+
+            fn serialized_size(&self) -> usize {
+                self.id().serialized_size() + self.serialized_body_size()
+            }
         }
 
         impl Deserialize for $typname {
@@ -554,6 +591,15 @@ macro_rules! proto_enum_with_type {
                     }),*
                 }
             }
+
+            #[allow(unused_variables)]
+            pub fn serialized_body_size(&self) -> usize {
+                match &self {
+                    $($typname::$nam$((instead_of_ident!($bod, bod)))? => {
+                        0 $(+ instead_of_ident!($bod, bod).serialized_size())?
+                    }),*
+                }
+            }
         }
 
         #[cfg(all(test, feature = "std"))]
@@ -612,6 +658,10 @@ macro_rules! proto_str_enum {
                 to.serialize_other(&name)?;
                 self.serialize_body(to)
             }
+
+            fn serialized_size(&self) -> usize {
+                crate::types::str_serialized_size(self.name()) + self.serialized_body_size()
+            }
         }
 
         impl Deserialize for $typname {
@@ -653,6 +703,15 @@ macro_rules! proto_str_enum {
                     }),*
                 }
             }
+
+            #[allow(unused_variables)]
+            pub fn serialized_body_size(&self) -> usize {
+                match &self {
+                    $($typname::$nam$((instead_of_ident!($bod, bod)))? => {
+                        0 $(+ instead_of_ident!($bod, bod).serialized_size())?
+                    }),*
+                }
+            }
         }
 
         impl From<&$typname> for String {
@@ -710,6 +769,10 @@ macro_rules! proto_byte_flag {
             fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
                 to.serialize_byte(self.0)
             }
+
+            fn serialized_size(&self) -> usize {
+                1
+            }
         }
 
         impl Deserialize for $typname {
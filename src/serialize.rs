@@ -1,8 +1,13 @@
 use alloc::{string::String, fmt};
 
+// more variants can show up (and Io only exists with std), so matches need a wildcard arm
+#[non_exhaustive]
 pub enum SerializeErr {
     FailedJsonEncode(String),
     CannotSerialize(String),
+    BufferFull { needed: usize, available: usize },
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
 }
 
 impl fmt::Display for SerializeErr {
@@ -15,6 +20,13 @@ impl fmt::Display for SerializeErr {
             CannotSerialize(message) => {
                 f.write_fmt(format_args!("cannot serialize value, invalid representation: {:?}", message))
             }
+            BufferFull { needed, available } => {
+                f.write_fmt(format_args!("buffer is full, needed {} more bytes but only {} are left", needed, available))
+            }
+            #[cfg(feature = "std")]
+            Io(err) => {
+                f.write_fmt(format_args!("io error while serializing: {}", err))
+            }
         }
     }
 }
@@ -28,10 +40,26 @@ impl fmt::Debug for SerializeErr {
 #[cfg(feature = "std")]
 impl std::error::Error for SerializeErr {}
 
+#[cfg(feature = "std")]
+impl From<std::io::Error> for SerializeErr {
+    fn from(err: std::io::Error) -> Self {
+        SerializeErr::Io(err)
+    }
+}
+
 pub type SerializeResult = Result<(), SerializeErr>;
 
 pub trait Serialize: Sized {
     fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult;
+
+    // the number of bytes mc_serialize will write. by default this serializes into a SizeCounter, types which know
+    // their size up front override it
+    fn serialized_size(&self) -> usize {
+        let mut counter = SizeCounter::default();
+        // a value that fails to serialize can't be written at all, so the partial count is as good as any
+        let _ = self.mc_serialize(&mut counter);
+        counter.size()
+    }
 }
 
 pub trait Serializer: Sized {
@@ -45,3 +73,241 @@ pub trait Serializer: Sized {
         other.mc_serialize(self)
     }
 }
+
+// throws away everything it's given, only keeping track of how much there was
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SizeCounter {
+    size: usize,
+}
+
+impl Serializer for SizeCounter {
+    fn serialize_bytes(&mut self, data: &[u8]) -> SerializeResult {
+        self.size += data.len();
+        Ok(())
+    }
+
+    fn serialize_byte(&mut self, _: u8) -> SerializeResult {
+        self.size += 1;
+        Ok(())
+    }
+}
+
+impl SizeCounter {
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+// writes into a fixed size buffer, failing (without writing anything) when a write doesn't fit
+#[derive(Debug)]
+pub struct SliceSerializer<'a> {
+    target: &'a mut [u8],
+    written: usize,
+}
+
+impl Serializer for SliceSerializer<'_> {
+    fn serialize_bytes(&mut self, data: &[u8]) -> SerializeResult {
+        let available = self.target.len() - self.written;
+        if data.len() > available {
+            return Err(SerializeErr::BufferFull { needed: data.len(), available });
+        }
+
+        self.target[self.written..self.written + data.len()].copy_from_slice(data);
+        self.written += data.len();
+        Ok(())
+    }
+}
+
+impl<'a> SliceSerializer<'a> {
+    pub fn new(target: &'a mut [u8]) -> Self {
+        Self { target, written: 0 }
+    }
+
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.target.len() - self.written
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.target[..self.written]
+    }
+
+    pub fn into_bytes(self) -> &'a mut [u8] {
+        &mut self.target[..self.written]
+    }
+}
+
+// every serialize_bytes call is a write_all, so wrap unbuffered writers (like a TcpStream) in a BufWriter
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IoSerializer<W> {
+    target: W,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> Serializer for IoSerializer<W> {
+    fn serialize_bytes(&mut self, data: &[u8]) -> SerializeResult {
+        self.target.write_all(data).map_err(SerializeErr::Io)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> IoSerializer<W> {
+    pub fn new(target: W) -> Self {
+        Self { target }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.target
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.target
+    }
+
+    pub fn into_inner(self) -> W {
+        self.target
+    }
+}
+
+// a BytesMut grows as needed, anything with a fixed capacity fails like SliceSerializer does
+#[cfg(feature = "bytes")]
+#[derive(Debug)]
+pub struct BufMutSerializer<B> {
+    target: B,
+}
+
+#[cfg(feature = "bytes")]
+impl<B: bytes::BufMut> Serializer for BufMutSerializer<B> {
+    fn serialize_bytes(&mut self, data: &[u8]) -> SerializeResult {
+        let available = self.target.remaining_mut();
+        if data.len() > available {
+            return Err(SerializeErr::BufferFull { needed: data.len(), available });
+        }
+
+        self.target.put_slice(data);
+        Ok(())
+    }
+
+    fn serialize_byte(&mut self, byte: u8) -> SerializeResult {
+        if !self.target.has_remaining_mut() {
+            return Err(SerializeErr::BufferFull { needed: 1, available: 0 });
+        }
+
+        self.target.put_u8(byte);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<B: bytes::BufMut> BufMutSerializer<B> {
+    pub fn new(target: B) -> Self {
+        Self { target }
+    }
+
+    pub fn get_ref(&self) -> &B {
+        &self.target
+    }
+
+    pub fn get_mut(&mut self) -> &mut B {
+        &mut self.target
+    }
+
+    pub fn into_inner(self) -> B {
+        self.target
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::VarInt;
+    use alloc::string::ToString;
+
+    fn test_value() -> (VarInt, String) {
+        (VarInt(300), "hello".to_string())
+    }
+
+    fn serialize_test_value<S: Serializer>(to: &mut S) -> SerializeResult {
+        let (count, text) = test_value();
+        to.serialize_other(&count)?;
+        to.serialize_other(&text)
+    }
+
+    const TEST_VALUE_BYTES: &[u8] = b"\xAC\x02\x05hello";
+
+    #[test]
+    fn test_size_counter() {
+        let mut counter = SizeCounter::default();
+        serialize_test_value(&mut counter).expect("counting never fails");
+        assert_eq!(counter.size(), TEST_VALUE_BYTES.len());
+
+        let (count, text) = test_value();
+        assert_eq!(count.serialized_size() + text.serialized_size(), TEST_VALUE_BYTES.len());
+    }
+
+    struct TestBody;
+
+    impl crate::protocol::HasPacketBody for TestBody {
+        fn mc_serialize_body<S: Serializer>(&self, to: &mut S) -> SerializeResult {
+            serialize_test_value(to)
+        }
+    }
+
+    #[test]
+    fn test_default_body_size() {
+        use crate::protocol::HasPacketBody;
+        assert_eq!(TestBody.serialized_body_size(), TEST_VALUE_BYTES.len());
+    }
+
+    #[test]
+    fn test_slice_serializer() {
+        let mut buf = [0u8; 16];
+        let mut out = SliceSerializer::new(&mut buf);
+        serialize_test_value(&mut out).expect("fits");
+        assert_eq!(out.written(), TEST_VALUE_BYTES.len());
+        assert_eq!(out.remaining(), 16 - TEST_VALUE_BYTES.len());
+        assert_eq!(out.into_bytes(), TEST_VALUE_BYTES);
+
+        // the string doesn't fit, and nothing of it is written
+        let mut buf = [0u8; 5];
+        let mut out = SliceSerializer::new(&mut buf);
+        match serialize_test_value(&mut out) {
+            Err(SerializeErr::BufferFull { needed: 5, available: 2 }) => {}
+            other => panic!("expected the buffer to be full, got {:?}", other),
+        }
+        assert_eq!(out.as_bytes(), b"\xAC\x02\x05");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_io_serializer() {
+        let mut out = IoSerializer::new(std::io::Cursor::new(alloc::vec::Vec::new()));
+        serialize_test_value(&mut out).expect("writing to a vec never fails");
+        assert_eq!(out.into_inner().into_inner().as_slice(), TEST_VALUE_BYTES);
+
+        // a cursor over a slice can't grow, so it runs out of space
+        let mut buf = [0u8; 4];
+        let mut out = IoSerializer::new(&mut buf[..]);
+        assert!(matches!(serialize_test_value(&mut out), Err(SerializeErr::Io(_))));
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_buf_mut_serializer() {
+        let mut out = BufMutSerializer::new(bytes::BytesMut::new());
+        serialize_test_value(&mut out).expect("BytesMut grows");
+        assert_eq!(&out.into_inner()[..], TEST_VALUE_BYTES);
+
+        let mut buf = [0u8; 3];
+        let mut out = BufMutSerializer::new(&mut buf[..]);
+        match serialize_test_value(&mut out) {
+            Err(SerializeErr::BufferFull { needed: 5, available: 0 }) => {}
+            other => panic!("expected the buffer to be full, got {:?}", other),
+        }
+        assert_eq!(buf, [0xAC, 0x02, 0x05]);
+    }
+}
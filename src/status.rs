@@ -5,12 +5,20 @@ use crate::{
     SerializeErr, SerializeResult,
 };
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
 use alloc::{string::String, fmt, vec::Vec, borrow::ToOwned};
 use alloc::format;
 
 #[cfg(all(test, feature = "std"))]
 use crate::protocol::TestRandom;
 
+mod legacy;
+pub use legacy::*;
+#[cfg(all(feature = "std", any(feature = "v1_15_2", feature = "v1_16_3")))]
+mod ping;
+#[cfg(all(feature = "std", any(feature = "v1_15_2", feature = "v1_16_3")))]
+pub use ping::*;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct StatusSpec {
     pub version: Option<StatusVersionSpec>,
@@ -18,6 +26,15 @@ pub struct StatusSpec {
     pub description: Chat,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub favicon: Option<StatusFaviconSpec>,
+    // FML1 (forge for 1.7 - 1.12)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modinfo: Option<StatusModInfoSpec>,
+    // FML2 (forge for 1.13 and up)
+    #[serde(rename = "forgeData", skip_serializing_if = "Option::is_none")]
+    pub forge_data: Option<StatusForgeDataSpec>,
+    // any other keys, kept so that a status passes through unchanged
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
 }
 
 impl McSerialize for StatusSpec {
@@ -58,6 +75,9 @@ impl TestRandom for StatusSpec {
             },
             favicon: None,
             description: Chat::test_gen_random(),
+            modinfo: None,
+            forge_data: None,
+            other: Map::new(),
         }
     }
 }
@@ -83,12 +103,133 @@ pub struct StatusPlayerSampleSpec {
     pub id: UUID4,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StatusModInfoSpec {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(rename = "modList", default = "Vec::default")]
+    pub mod_list: Vec<StatusModInfoModSpec>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StatusModInfoModSpec {
+    #[serde(rename = "modid")]
+    pub mod_id: String,
+    pub version: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StatusForgeDataSpec {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<StatusForgeChannelSpec>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mods: Vec<StatusForgeModSpec>,
+    #[serde(rename = "fmlNetworkVersion", skip_serializing_if = "Option::is_none")]
+    pub fml_network_version: Option<i32>,
+    // set when the lists above were cut short to keep the response small
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    pub truncated: bool,
+    // such as "d", the compressed mod list newer versions send instead
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StatusForgeChannelSpec {
+    pub res: String,
+    pub version: String,
+    pub required: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StatusForgeModSpec {
+    #[serde(rename = "modId")]
+    pub mod_id: String,
+    pub modmarker: String,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct StatusFaviconSpec {
     pub content_type: String,
     pub data: Vec<u8>,
 }
 
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+const PNG_CONTENT_TYPE: &str = "image/png";
+pub const FAVICON_SIZE: u32 = 64;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FaviconErr {
+    BadContentType(String),
+    BadSignature,
+    MissingHeader,
+    BadDimensions { width: u32, height: u32 },
+}
+
+impl fmt::Display for FaviconErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use FaviconErr::*;
+        match self {
+            BadContentType(content_type) => {
+                f.write_fmt(format_args!("favicon must be {}, got {:?}", PNG_CONTENT_TYPE, content_type))
+            }
+            BadSignature => f.write_str("favicon is not a png (bad signature)"),
+            MissingHeader => f.write_str("favicon png does not start with an IHDR chunk"),
+            BadDimensions { width, height } => f.write_fmt(format_args!(
+                "favicon must be {}x{}, got {}x{}",
+                FAVICON_SIZE, FAVICON_SIZE, width, height
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FaviconErr {}
+
+impl StatusFaviconSpec {
+    pub fn from_png(data: Vec<u8>) -> Result<Self, FaviconErr> {
+        let out = Self {
+            content_type: PNG_CONTENT_TYPE.to_owned(),
+            data,
+        };
+        out.validate()?;
+        Ok(out)
+    }
+
+    // what the vanilla client accepts: a 64x64 png
+    pub fn validate(&self) -> Result<(), FaviconErr> {
+        if self.content_type != PNG_CONTENT_TYPE {
+            return Err(FaviconErr::BadContentType(self.content_type.clone()));
+        }
+
+        match png_dimensions(self.data.as_slice())? {
+            (FAVICON_SIZE, FAVICON_SIZE) => Ok(()),
+            (width, height) => Err(FaviconErr::BadDimensions { width, height }),
+        }
+    }
+
+    pub fn png_bytes(&self) -> Result<&[u8], FaviconErr> {
+        self.validate()?;
+        Ok(self.data.as_slice())
+    }
+}
+
+// reads width and height from the IHDR chunk, which the png spec requires to come first, without decoding anything
+pub fn png_dimensions(data: &[u8]) -> Result<(u32, u32), FaviconErr> {
+    if data.len() < PNG_SIGNATURE.len() || data[..PNG_SIGNATURE.len()] != PNG_SIGNATURE {
+        return Err(FaviconErr::BadSignature);
+    }
+
+    // length (4), type (4), width (4), height (4)
+    let chunk = &data[PNG_SIGNATURE.len()..];
+    if chunk.len() < 16 || &chunk[4..8] != b"IHDR" {
+        return Err(FaviconErr::MissingHeader);
+    }
+
+    let read_u32 = move |at: usize| u32::from_be_bytes([chunk[at], chunk[at + 1], chunk[at + 2], chunk[at + 3]]);
+    Ok((read_u32(8), read_u32(12)))
+}
+
 impl Serialize for StatusFaviconSpec {
     fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
     where
@@ -188,4 +329,97 @@ fn str_take<'a, E, V>(
     } else {
         Ok(target.split_at(n))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut out = PNG_SIGNATURE.to_vec();
+        out.extend_from_slice(&13u32.to_be_bytes());
+        out.extend_from_slice(b"IHDR");
+        out.extend_from_slice(&width.to_be_bytes());
+        out.extend_from_slice(&height.to_be_bytes());
+        out.extend_from_slice(&[8, 6, 0, 0, 0]);
+        out
+    }
+
+    #[test]
+    fn test_favicon_from_png() {
+        let favicon = StatusFaviconSpec::from_png(png_header(64, 64)).expect("valid favicon");
+        assert_eq!(favicon.png_bytes().expect("valid favicon"), png_header(64, 64).as_slice());
+
+        let json = serde_json::to_string(&favicon).expect("serializes");
+        assert!(json.starts_with("\"data:image/png;base64,iVBORw0KGgo"));
+        let parsed: StatusFaviconSpec = serde_json::from_str(json.as_str()).expect("deserializes");
+        assert_eq!(parsed, favicon);
+        assert_eq!(parsed.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_favicon_errors() {
+        assert_eq!(StatusFaviconSpec::from_png(png_header(128, 64)), Err(FaviconErr::BadDimensions { width: 128, height: 64 }));
+        assert_eq!(StatusFaviconSpec::from_png(b"GIF89a".to_vec()), Err(FaviconErr::BadSignature));
+        assert_eq!(StatusFaviconSpec::from_png(PNG_SIGNATURE.to_vec()), Err(FaviconErr::MissingHeader));
+
+        let mut no_header = png_header(64, 64);
+        no_header[12..16].copy_from_slice(b"IDAT");
+        assert_eq!(png_dimensions(no_header.as_slice()), Err(FaviconErr::MissingHeader));
+
+        let jpeg = StatusFaviconSpec { content_type: "image/jpeg".to_owned(), data: png_header(64, 64) };
+        assert_eq!(jpeg.validate(), Err(FaviconErr::BadContentType("image/jpeg".to_owned())));
+        assert_eq!(jpeg.png_bytes(), Err(FaviconErr::BadContentType("image/jpeg".to_owned())));
+    }
+
+    fn assert_round_trips(raw: &str) -> StatusSpec {
+        let status: StatusSpec = serde_json::from_str(raw).expect("valid status");
+        let expected: Value = serde_json::from_str(raw).expect("valid json");
+        assert_eq!(serde_json::to_value(&status).expect("serializes"), expected);
+        status
+    }
+
+    #[test]
+    fn test_status_fml1() {
+        let status = assert_round_trips(include_str!("testdata/status_fml1.json"));
+        let modinfo = status.modinfo.expect("has modinfo");
+        assert_eq!(modinfo.kind.as_str(), "FML");
+        assert_eq!(modinfo.mod_list.len(), 5);
+        assert_eq!(modinfo.mod_list[4], StatusModInfoModSpec { mod_id: "jei".to_owned(), version: "4.16.1.301".to_owned() });
+        assert!(status.forge_data.is_none());
+        assert!(status.other.is_empty());
+    }
+
+    #[test]
+    fn test_status_fml2() {
+        let status = assert_round_trips(include_str!("testdata/status_fml2.json"));
+        let forge_data = status.forge_data.expect("has forgeData");
+        assert_eq!(forge_data.channels[1], StatusForgeChannelSpec {
+            res: "jei:channel".to_owned(),
+            version: "7.6.1".to_owned(),
+            required: true,
+        });
+        assert_eq!(forge_data.mods[0], StatusForgeModSpec { mod_id: "forge".to_owned(), modmarker: "ANY".to_owned() });
+        assert_eq!(forge_data.fml_network_version, Some(2));
+        assert!(forge_data.truncated);
+        assert!(forge_data.other.contains_key("d"));
+        assert!(status.modinfo.is_none());
+        assert_eq!(status.other.get("previewsChat"), Some(&Value::Bool(false)));
+        assert_eq!(status.other.get("enforcesSecureChat"), Some(&Value::Bool(true)));
+    }
+
+    #[test]
+    fn test_status_fml2_without_lists() {
+        // newer forge only sends the compressed "d" form, without channels or mods
+        let mut raw: Value = serde_json::from_str(include_str!("testdata/status_fml2.json")).expect("valid json");
+        let forge_data = raw["forgeData"].as_object_mut().expect("has forgeData");
+        forge_data.remove("channels");
+        forge_data.remove("mods");
+
+        let status = assert_round_trips(raw.to_string().as_str());
+        let forge_data = status.forge_data.expect("has forgeData");
+        assert!(forge_data.channels.is_empty());
+        assert!(forge_data.mods.is_empty());
+        assert!(forge_data.other.contains_key("d"));
+    }
+}
@@ -0,0 +1,308 @@
+use super::StatusSpec;
+use crate::utils::take;
+use crate::{
+    Deserialize, DeserializeErr, DeserializeResult, Deserialized, SerializeErr, SerializeResult, Serialize,
+    Serializer,
+};
+use alloc::{string::{String, ToString}, vec::Vec, collections::BTreeMap, format};
+
+// pre 1.7 clients open with this byte instead of a varint length, which is never the case for a modern handshake
+pub const LEGACY_PING_ID: u8 = 0xFE;
+pub const LEGACY_KICK_ID: u8 = 0xFF;
+
+const LEGACY_PING_PAYLOAD: u8 = 0x01;
+const LEGACY_PLUGIN_MESSAGE_ID: u8 = 0xFA;
+const LEGACY_PING_CHANNEL: &str = "MC|PingHost";
+
+pub fn is_legacy_ping(data: &[u8]) -> bool {
+    data.first() == Some(&LEGACY_PING_ID)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LegacyPingRequest {
+    // beta 1.8 - 1.3, just 0xFE
+    Beta,
+    // 1.4 - 1.5, 0xFE 0x01
+    V1_4,
+    // 1.6, 0xFE 0x01 followed by a MC|PingHost plugin message
+    V1_6(LegacyPingHost),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegacyPingHost {
+    pub protocol_version: u8,
+    pub hostname: String,
+    pub port: i32,
+}
+
+// a 1.4 client sends nothing after 0xFE 0x01, so only call this once the client has stopped sending
+impl Deserialize for LegacyPingRequest {
+    fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
+        let Deserialized { value: id, data } = u8::mc_deserialize(data)?;
+        if id != LEGACY_PING_ID {
+            return Err(DeserializeErr::CannotUnderstandValue(format!("legacy ping starts with {:#04x}", id)));
+        }
+
+        match data.first() {
+            None => return Deserialized::ok(LegacyPingRequest::Beta, data),
+            Some(&LEGACY_PING_PAYLOAD) => {}
+            Some(other) => {
+                return Err(DeserializeErr::CannotUnderstandValue(format!("bad legacy ping payload {:#04x}", other)))
+            }
+        }
+
+        let data = &data[1..];
+        match data.first() {
+            Some(&LEGACY_PLUGIN_MESSAGE_ID) => {}
+            _ => return Deserialized::ok(LegacyPingRequest::V1_4, data),
+        }
+
+        let Deserialized { value: channel, data } = read_utf16_string(&data[1..])?;
+        if channel != LEGACY_PING_CHANNEL {
+            return Err(DeserializeErr::CannotUnderstandValue(format!("unexpected legacy ping channel {}", channel)));
+        }
+
+        let Deserialized { value: length, data } = u16::mc_deserialize(data)?;
+        let Deserialized { value: body, data: rest } = take(length as usize, data)?;
+        let Deserialized { value: protocol_version, data: body } = u8::mc_deserialize(body)?;
+        let Deserialized { value: hostname, data: body } = read_utf16_string(body)?;
+        let Deserialized { value: port, data: body } = i32::mc_deserialize(body)?;
+        if !body.is_empty() {
+            return Err(DeserializeErr::CannotUnderstandValue(format!("{} extra bytes in MC|PingHost", body.len())));
+        }
+
+        Deserialized::ok(LegacyPingRequest::V1_6(LegacyPingHost { protocol_version, hostname, port }), rest)
+    }
+}
+
+impl Serialize for LegacyPingRequest {
+    fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
+        let host = match self {
+            LegacyPingRequest::Beta => return to.serialize_byte(LEGACY_PING_ID),
+            LegacyPingRequest::V1_4 => return to.serialize_bytes(&[LEGACY_PING_ID, LEGACY_PING_PAYLOAD]),
+            LegacyPingRequest::V1_6(host) => host,
+        };
+
+        // protocol version, hostname length, the hostname as UTF-16 and the port
+        let body_length = 7 + (2 * utf16_length(host.hostname.as_str())? as usize);
+        if body_length > u16::MAX as usize {
+            return Err(SerializeErr::CannotSerialize(format!(
+                "legacy ping hostname too long ({} byte MC|PingHost body)", body_length)));
+        }
+
+        to.serialize_bytes(&[LEGACY_PING_ID, LEGACY_PING_PAYLOAD, LEGACY_PLUGIN_MESSAGE_ID])?;
+        write_utf16_string(LEGACY_PING_CHANNEL, to)?;
+        to.serialize_other(&(body_length as u16))?;
+        to.serialize_other(&host.protocol_version)?;
+        write_utf16_string(host.hostname.as_str(), to)?;
+        to.serialize_other(&host.port)
+    }
+}
+
+// sent back as a kick packet, which the client parses instead of disconnecting
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegacyPingResponse {
+    // beta clients only understand motd§online§max, so the version is not sent (and formatting is not allowed)
+    pub beta: bool,
+    pub protocol_version: i32,
+    pub version_name: String,
+    pub motd: String,
+    pub online_players: i32,
+    pub max_players: i32,
+}
+
+impl LegacyPingResponse {
+    pub fn from_status(status: &StatusSpec, request: &LegacyPingRequest) -> Self {
+        let beta = *request == LegacyPingRequest::Beta;
+        let motd = if beta {
+            status.description.to_plain_text(&BTreeMap::new())
+        } else {
+            status.description.render(&BTreeMap::new()).to_traditional().unwrap_or_default()
+        };
+
+        let (protocol_version, version_name) = match &status.version {
+            Some(version) => (version.protocol, version.name.clone()),
+            None => (-1, String::new()),
+        };
+
+        Self {
+            beta,
+            protocol_version,
+            version_name,
+            motd,
+            online_players: status.players.online,
+            max_players: status.players.max,
+        }
+    }
+
+    pub fn kick_message(&self) -> String {
+        if self.beta {
+            // § is the separator here, so it can't appear anywhere else
+            let motd: String = self.motd.chars().filter(move |c| *c != '§').collect();
+            format!("{}§{}§{}", motd, self.online_players, self.max_players)
+        } else {
+            format!("§1\0{}\0{}\0{}\0{}\0{}",
+                    self.protocol_version, self.version_name, self.motd, self.online_players, self.max_players)
+        }
+    }
+}
+
+impl Serialize for LegacyPingResponse {
+    fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
+        to.serialize_byte(LEGACY_KICK_ID)?;
+        write_utf16_string(self.kick_message().as_str(), to)
+    }
+}
+
+impl Deserialize for LegacyPingResponse {
+    fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
+        let Deserialized { value: id, data } = u8::mc_deserialize(data)?;
+        if id != LEGACY_KICK_ID {
+            return Err(DeserializeErr::CannotUnderstandValue(format!("legacy kick starts with {:#04x}", id)));
+        }
+
+        read_utf16_string(data)?.try_map(move |message| {
+            let bad_value = || DeserializeErr::CannotUnderstandValue(format!("bad legacy ping response {:?}", message));
+            let parse_num = |raw: &str| raw.parse::<i32>().map_err(|_| bad_value());
+            match message.strip_prefix("§1\0") {
+                Some(rest) => {
+                    let parts: Vec<&str> = rest.split('\0').collect();
+                    if parts.len() != 5 {
+                        return Err(bad_value());
+                    }
+
+                    Ok(Self {
+                        beta: false,
+                        protocol_version: parse_num(parts[0])?,
+                        version_name: parts[1].to_string(),
+                        motd: parts[2].to_string(),
+                        online_players: parse_num(parts[3])?,
+                        max_players: parse_num(parts[4])?,
+                    })
+                }
+                None => {
+                    let mut parts = message.rsplitn(3, '§');
+                    let (max, online, motd) = match (parts.next(), parts.next(), parts.next()) {
+                        (Some(max), Some(online), Some(motd)) => (max, online, motd),
+                        _ => return Err(bad_value()),
+                    };
+
+                    Ok(Self {
+                        beta: true,
+                        protocol_version: -1,
+                        version_name: String::new(),
+                        motd: motd.to_string(),
+                        online_players: parse_num(online)?,
+                        max_players: parse_num(max)?,
+                    })
+                }
+            }
+        })
+    }
+}
+
+fn utf16_length(data: &str) -> Result<u16, SerializeErr> {
+    let length = data.encode_utf16().count();
+    if length > u16::MAX as usize {
+        Err(SerializeErr::CannotSerialize(format!("legacy string too long ({} chars)", length)))
+    } else {
+        Ok(length as u16)
+    }
+}
+
+// legacy strings are a u16 count of chars followed by those chars in UTF-16BE
+fn write_utf16_string<S: Serializer>(data: &str, to: &mut S) -> SerializeResult {
+    to.serialize_other(&utf16_length(data)?)?;
+    for unit in data.encode_utf16() {
+        to.serialize_bytes(&unit.to_be_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn read_utf16_string(data: &[u8]) -> DeserializeResult<'_, String> {
+    let Deserialized { value: length, data } = u16::mc_deserialize(data)?;
+    let Deserialized { value: raw, data } = take(2 * (length as usize), data)?;
+    let units: Vec<u16> = raw.chunks(2).map(move |unit| u16::from_be_bytes([unit[0], unit[1]])).collect();
+    match String::from_utf16(units.as_slice()) {
+        Ok(out) => Deserialized::ok(out, data),
+        Err(err) => Err(DeserializeErr::CannotUnderstandValue(format!("bad utf-16 string {}", err))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BytesSerializer;
+
+    fn serialize<T: Serialize>(value: &T) -> Vec<u8> {
+        let mut out = BytesSerializer::default();
+        value.mc_serialize(&mut out).expect("serialize");
+        out.into_bytes()
+    }
+
+    #[test]
+    fn test_legacy_ping_requests() {
+        // a real 1.6.4 client pinging localhost:25565
+        let mut raw = alloc::vec![0xFE, 0x01, 0xFA, 0x00, 0x0B];
+        for unit in "MC|PingHost".encode_utf16() {
+            raw.extend_from_slice(&unit.to_be_bytes());
+        }
+        raw.extend_from_slice(&[0x00, 0x19, 0x4E, 0x00, 0x09]);
+        for unit in "localhost".encode_utf16() {
+            raw.extend_from_slice(&unit.to_be_bytes());
+        }
+        raw.extend_from_slice(&[0x00, 0x00, 0x63, 0xDD]);
+
+        assert!(is_legacy_ping(raw.as_slice()));
+        assert!(!is_legacy_ping(&[0x10, 0x00]));
+
+        let expected = LegacyPingRequest::V1_6(LegacyPingHost {
+            protocol_version: 78,
+            hostname: "localhost".to_string(),
+            port: 25565,
+        });
+        let parsed = LegacyPingRequest::mc_deserialize(raw.as_slice()).expect("parses");
+        assert_eq!(parsed.value, expected);
+        assert!(parsed.data.is_empty());
+        assert_eq!(serialize(&expected), raw);
+
+        assert_eq!(LegacyPingRequest::mc_deserialize(&[0xFE]).expect("beta").value, LegacyPingRequest::Beta);
+        assert_eq!(LegacyPingRequest::mc_deserialize(&[0xFE, 0x01]).expect("1.4").value, LegacyPingRequest::V1_4);
+        assert!(LegacyPingRequest::mc_deserialize(&[0xFE, 0x02]).is_err());
+        assert!(LegacyPingRequest::mc_deserialize(&raw[..raw.len() - 1]).is_err());
+
+        let too_long = LegacyPingRequest::V1_6(LegacyPingHost {
+            protocol_version: 78,
+            hostname: "a".repeat(32765),
+            port: 25565,
+        });
+        let mut out = BytesSerializer::default();
+        assert!(matches!(too_long.mc_serialize(&mut out), Err(SerializeErr::CannotSerialize(_))));
+        assert!(out.into_bytes().is_empty());
+    }
+
+    #[test]
+    fn test_legacy_ping_response() {
+        let status: StatusSpec = serde_json::from_str(r#"{"version":{"name":"1.16.3","protocol":753},
+            "players":{"max":20,"online":3},"description":{"text":"Hi ","extra":[{"text":"there","color":"red"}]}}"#)
+            .expect("valid status");
+
+        let response = LegacyPingResponse::from_status(&status, &LegacyPingRequest::V1_4);
+        assert_eq!(response.kick_message(), "§1\u{0}753\u{0}1.16.3\u{0}Hi §cthere\u{0}3\u{0}20");
+
+        let raw = serialize(&response);
+        assert_eq!(&raw[..3], &[0xFF, 0x00, 0x1D]);
+        assert_eq!(&raw[3..7], &[0x00, 0xA7, 0x00, 0x31]);
+        let parsed = LegacyPingResponse::mc_deserialize(raw.as_slice()).expect("parses");
+        assert_eq!(parsed.value, response);
+        assert!(parsed.data.is_empty());
+
+        let beta = LegacyPingResponse::from_status(&status, &LegacyPingRequest::Beta);
+        assert_eq!(beta.kick_message(), "Hi there§3§20");
+        // the version isn't sent to beta clients, so it can't come back
+        let parsed = LegacyPingResponse::mc_deserialize(serialize(&beta).as_slice()).expect("parses").value;
+        assert_eq!((parsed.beta, parsed.protocol_version, parsed.motd.as_str()), (true, -1, "Hi there"));
+        assert_eq!((parsed.online_players, parsed.max_players), (3, 20));
+    }
+}
@@ -0,0 +1,465 @@
+use super::StatusSpec;
+use crate::protocol::{HasPacketId, Id, Packet, PacketDirection, PacketErr, RawPacket, State};
+use crate::types::{BytesSerializer, VarInt};
+use crate::{Deserialize, DeserializeErr, SerializeErr, Serializer};
+use alloc::{string::String, vec::Vec, fmt};
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// the status packets haven't changed between the versions this crate implements, so whichever one is compiled in
+// frames them. the protocol version reported in the handshake is still up to the caller
+#[cfg(feature = "v1_16_3")]
+use crate::v1_16_3::{
+    Packet753 as StatusPacket, RawPacket753 as RawStatusPacket, HandshakeSpec, HandshakeNextState, StatusRequestSpec,
+    StatusPingSpec,
+};
+#[cfg(not(feature = "v1_16_3"))]
+use crate::v1_15_2::{
+    Packet578 as StatusPacket, RawPacket578 as RawStatusPacket, HandshakeSpec, HandshakeNextState, StatusRequestSpec,
+    StatusPingSpec,
+};
+
+#[cfg(feature = "futures")]
+use futures_util::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+// the same limit vanilla uses, a packet length is at most a 3 byte varint
+const MAX_PACKET_LENGTH: i32 = (1 << 21) - 1;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusPingResult {
+    pub status: StatusSpec,
+    pub latency: Duration,
+}
+
+pub enum StatusPingErr {
+    Io(io::Error),
+    SerializeFailed(SerializeErr),
+    DeserializeFailed(DeserializeErr),
+    BadPacketLength(i32),
+    UnexpectedPacket(i32),
+    ExtraData(Vec<u8>),
+    PayloadMismatch { sent: i64, received: i64 },
+}
+
+impl fmt::Display for StatusPingErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use StatusPingErr::*;
+        match self {
+            Io(err) => f.write_fmt(format_args!("io error during ping: {}", err)),
+            SerializeFailed(err) => f.write_fmt(format_args!("failed to serialize ping packet: {:?}", err)),
+            DeserializeFailed(err) => f.write_fmt(format_args!("failed to deserialize ping packet: {:?}", err)),
+            BadPacketLength(length) => f.write_fmt(format_args!("bad packet length {}", length)),
+            UnexpectedPacket(id) => f.write_fmt(format_args!("unexpected packet id {:#04x}", id)),
+            ExtraData(data) => f.write_fmt(format_args!("extra data unparsed at end of packet: {:?}", data)),
+            PayloadMismatch { sent, received } => {
+                f.write_fmt(format_args!("pong payload {} does not match ping payload {}", received, sent))
+            }
+        }
+    }
+}
+
+impl fmt::Debug for StatusPingErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <dyn fmt::Display>::fmt(self, f)
+    }
+}
+
+impl std::error::Error for StatusPingErr {}
+
+impl From<io::Error> for StatusPingErr {
+    fn from(err: io::Error) -> Self {
+        StatusPingErr::Io(err)
+    }
+}
+
+impl From<SerializeErr> for StatusPingErr {
+    fn from(err: SerializeErr) -> Self {
+        StatusPingErr::SerializeFailed(err)
+    }
+}
+
+impl From<DeserializeErr> for StatusPingErr {
+    fn from(err: DeserializeErr) -> Self {
+        StatusPingErr::DeserializeFailed(err)
+    }
+}
+
+impl From<PacketErr> for StatusPingErr {
+    fn from(err: PacketErr) -> Self {
+        match err {
+            PacketErr::UnknownId(id) => StatusPingErr::UnexpectedPacket(id.id),
+            PacketErr::DeserializeFailed(err) => StatusPingErr::DeserializeFailed(err),
+            PacketErr::ExtraData(data) => StatusPingErr::ExtraData(data),
+        }
+    }
+}
+
+// runs handshake -> status request -> response -> ping -> pong over a fresh (uncompressed, unencrypted) connection,
+// the address and port are only what's reported in the handshake, the transport must already be connected
+pub fn ping<T: Read + Write>(
+    transport: &mut T,
+    address: &str,
+    port: u16,
+    protocol_version: i32,
+) -> Result<StatusPingResult, StatusPingErr> {
+    transport.write_all(status_request(address, port, protocol_version)?.as_slice())?;
+    transport.flush()?;
+    let status = expect_response(read_packet(transport)?)?;
+
+    let payload = ping_payload();
+    let sent_at = Instant::now();
+    transport.write_all(encode_packet(&StatusPacket::StatusPing(StatusPingSpec { payload }))?.as_slice())?;
+    transport.flush()?;
+    let received = expect_pong(read_packet(transport)?)?;
+    let latency = sent_at.elapsed();
+
+    finish(status, latency, payload, received)
+}
+
+// the same as ping, but over an async transport
+#[cfg(feature = "futures")]
+pub async fn ping_async<T: AsyncRead + AsyncWrite + Unpin>(
+    transport: &mut T,
+    address: &str,
+    port: u16,
+    protocol_version: i32,
+) -> Result<StatusPingResult, StatusPingErr> {
+    transport.write_all(status_request(address, port, protocol_version)?.as_slice()).await?;
+    transport.flush().await?;
+    let status = expect_response(read_packet_async(transport).await?)?;
+
+    let payload = ping_payload();
+    let sent_at = Instant::now();
+    transport.write_all(encode_packet(&StatusPacket::StatusPing(StatusPingSpec { payload }))?.as_slice()).await?;
+    transport.flush().await?;
+    let received = expect_pong(read_packet_async(transport).await?)?;
+    let latency = sent_at.elapsed();
+
+    finish(status, latency, payload, received)
+}
+
+// the handshake and the status request, which are sent together
+fn status_request(address: &str, port: u16, protocol_version: i32) -> Result<Vec<u8>, StatusPingErr> {
+    let mut out = encode_packet(&StatusPacket::Handshake(HandshakeSpec {
+        version: VarInt(protocol_version),
+        server_address: String::from(address),
+        server_port: port,
+        next_state: HandshakeNextState::Status,
+    }))?;
+    out.extend(encode_packet(&StatusPacket::StatusRequest(StatusRequestSpec {}))?);
+    Ok(out)
+}
+
+fn ping_payload() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(move |since| since.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+fn expect_response(packet: StatusPacket) -> Result<StatusSpec, StatusPingErr> {
+    match packet {
+        StatusPacket::StatusResponse(body) => Ok(body.response),
+        other => Err(StatusPingErr::UnexpectedPacket(other.id().id)),
+    }
+}
+
+fn expect_pong(packet: StatusPacket) -> Result<i64, StatusPingErr> {
+    match packet {
+        StatusPacket::StatusPong(body) => Ok(body.payload),
+        other => Err(StatusPingErr::UnexpectedPacket(other.id().id)),
+    }
+}
+
+fn finish(status: StatusSpec, latency: Duration, sent: i64, received: i64) -> Result<StatusPingResult, StatusPingErr> {
+    if received != sent {
+        return Err(StatusPingErr::PayloadMismatch { sent, received });
+    }
+
+    Ok(StatusPingResult { status, latency })
+}
+
+// a length prefixed frame holding the packet id and body
+fn encode_packet<P: Packet>(packet: &P) -> Result<Vec<u8>, StatusPingErr> {
+    let length = Packet::serialized_size(packet);
+    let mut out = BytesSerializer::with_capacity(length + 3);
+    out.serialize_other(&VarInt(length as i32))?;
+    out.serialize_other(&packet.id())?;
+    packet.mc_serialize_body(&mut out)?;
+    Ok(out.into_bytes())
+}
+
+fn decode_packet(frame: &[u8], state: State, direction: PacketDirection) -> Result<StatusPacket, StatusPingErr> {
+    let id = VarInt::mc_deserialize(frame)?;
+    let raw = RawStatusPacket::create(Id { id: id.value.0, state, direction }, id.data)?;
+    Ok(raw.deserialize()?)
+}
+
+fn read_packet<R: Read>(from: &mut R) -> Result<StatusPacket, StatusPingErr> {
+    decode_packet(read_frame(from)?.as_slice(), State::Status, PacketDirection::ClientBound)
+}
+
+fn read_frame<R: Read>(from: &mut R) -> Result<Vec<u8>, StatusPingErr> {
+    let length = check_length(read_varint(from)?)?;
+    let mut data = alloc::vec![0u8; length];
+    from.read_exact(data.as_mut_slice())?;
+    Ok(data)
+}
+
+fn check_length(length: i32) -> Result<usize, StatusPingErr> {
+    if length <= 0 || length > MAX_PACKET_LENGTH {
+        Err(StatusPingErr::BadPacketLength(length))
+    } else {
+        Ok(length as usize)
+    }
+}
+
+// varints have to be read a byte at a time, there's no way to know the length ahead of time
+fn read_varint<R: Read>(from: &mut R) -> Result<i32, StatusPingErr> {
+    let mut data = Vec::with_capacity(5);
+    loop {
+        let mut byte = [0u8; 1];
+        from.read_exact(&mut byte)?;
+        data.push(byte[0]);
+        if byte[0] & 0x80 == 0 || data.len() == 5 {
+            break;
+        }
+    }
+
+    Ok(VarInt::mc_deserialize(data.as_slice())?.value.0)
+}
+
+#[cfg(feature = "futures")]
+async fn read_packet_async<R: AsyncRead + Unpin>(from: &mut R) -> Result<StatusPacket, StatusPingErr> {
+    let length = check_length(read_varint_async(from).await?)?;
+    let mut data = alloc::vec![0u8; length];
+    from.read_exact(data.as_mut_slice()).await?;
+    decode_packet(data.as_slice(), State::Status, PacketDirection::ClientBound)
+}
+
+#[cfg(feature = "futures")]
+async fn read_varint_async<R: AsyncRead + Unpin>(from: &mut R) -> Result<i32, StatusPingErr> {
+    let mut data = Vec::with_capacity(5);
+    loop {
+        let mut byte = [0u8; 1];
+        from.read_exact(&mut byte).await?;
+        data.push(byte[0]);
+        if byte[0] & 0x80 == 0 || data.len() == 5 {
+            break;
+        }
+    }
+
+    Ok(VarInt::mc_deserialize(data.as_slice())?.value.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::HasPacketBody;
+    use std::collections::VecDeque;
+
+    #[cfg(feature = "v1_16_3")]
+    use crate::v1_16_3::{StatusResponseSpec, StatusPongSpec};
+    #[cfg(not(feature = "v1_16_3"))]
+    use crate::v1_15_2::{StatusResponseSpec, StatusPongSpec};
+
+    // answers every complete packet written to it as soon as it arrives, just like a vanilla server would
+    struct FakeServer {
+        status: StatusSpec,
+        pong_offset: i64,
+        response_id: Option<i32>,
+        from_client: Vec<u8>,
+        to_client: VecDeque<u8>,
+        handshake: Option<HandshakeSpec>,
+    }
+
+    impl FakeServer {
+        fn new(status: StatusSpec) -> Self {
+            Self {
+                status,
+                pong_offset: 0,
+                response_id: None,
+                from_client: Vec::new(),
+                to_client: VecDeque::new(),
+                handshake: None,
+            }
+        }
+
+        fn respond(&mut self, packet: StatusPacket) {
+            let mut out = encode_packet(&packet).expect("encode packet");
+            // pretend the server sent some other packet with the same body
+            if let (StatusPacket::StatusResponse(_), Some(id)) = (&packet, self.response_id) {
+                let mut body = BytesSerializer::default();
+                body.serialize_other(&VarInt(id)).expect("write id");
+                packet.mc_serialize_body(&mut body).expect("write body");
+                let body = body.into_bytes();
+                let mut framed = BytesSerializer::default();
+                framed.serialize_other(&VarInt(body.len() as i32)).expect("write length");
+                framed.serialize_bytes(body.as_slice()).expect("write frame");
+                out = framed.into_bytes();
+            }
+            self.to_client.extend(out);
+        }
+
+        fn handle(&mut self, frame: Vec<u8>) {
+            let state = if self.handshake.is_some() { State::Status } else { State::Handshaking };
+            match decode_packet(frame.as_slice(), state, PacketDirection::ServerBound).expect("valid packet") {
+                StatusPacket::Handshake(body) => self.handshake = Some(body),
+                StatusPacket::StatusRequest(_) => {
+                    let response = self.status.clone();
+                    self.respond(StatusPacket::StatusResponse(StatusResponseSpec { response }));
+                }
+                StatusPacket::StatusPing(body) => {
+                    let payload = body.payload + self.pong_offset;
+                    self.respond(StatusPacket::StatusPong(StatusPongSpec { payload }));
+                }
+                other => panic!("unexpected packet {:?}", other),
+            }
+        }
+    }
+
+    impl Write for FakeServer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.from_client.extend_from_slice(buf);
+            loop {
+                let mut pending = self.from_client.as_slice();
+                let frame = match read_frame(&mut pending) {
+                    Ok(frame) => frame,
+                    Err(_) => break,
+                };
+                self.from_client = pending.to_vec();
+                self.handle(frame);
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Read for FakeServer {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.to_client.len());
+            for (idx, byte) in self.to_client.drain(..n).enumerate() {
+                buf[idx] = byte;
+            }
+            Ok(n)
+        }
+    }
+
+    fn test_status() -> StatusSpec {
+        serde_json::from_str(r#"{"version":{"name":"1.16.3","protocol":753},
+            "players":{"max":20,"online":1,"sample":[{"name":"Notch","id":"069a79f4-44e9-4726-a5be-fca90e38aaf5"}]},
+            "description":{"text":"A Minecraft Server"}}"#).expect("valid status")
+    }
+
+    fn assert_handshake(server: &FakeServer) {
+        assert_eq!(server.handshake, Some(HandshakeSpec {
+            version: VarInt(753),
+            server_address: String::from("localhost"),
+            server_port: 25565,
+            next_state: HandshakeNextState::Status,
+        }));
+        assert!(server.from_client.is_empty());
+        assert!(server.to_client.is_empty());
+    }
+
+    #[test]
+    fn test_ping() {
+        let mut server = FakeServer::new(test_status());
+        let result = ping(&mut server, "localhost", 25565, 753).expect("ping succeeds");
+        assert_eq!(result.status, test_status());
+        assert_handshake(&server);
+    }
+
+    #[test]
+    fn test_ping_errors() {
+        let mut server = FakeServer::new(test_status());
+        server.pong_offset = 1;
+        match ping(&mut server, "localhost", 25565, 753) {
+            Err(StatusPingErr::PayloadMismatch { sent, received }) => assert_eq!(sent + 1, received),
+            other => panic!("expected payload mismatch, got {:?}", other),
+        }
+
+        let mut server = FakeServer::new(test_status());
+        server.response_id = Some(0x02);
+        match ping(&mut server, "localhost", 25565, 753) {
+            Err(StatusPingErr::UnexpectedPacket(0x02)) => {}
+            other => panic!("expected unexpected packet, got {:?}", other),
+        }
+
+        // a server which never answers shows up as an eof
+        let mut silent: &[u8] = &[];
+        let mut transport = ReadWrite(&mut silent, Vec::new());
+        match ping(&mut transport, "localhost", 25565, 753) {
+            Err(StatusPingErr::Io(err)) => assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof),
+            other => panic!("expected eof, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "futures")]
+    impl AsyncRead for FakeServer {
+        fn poll_read(
+            self: core::pin::Pin<&mut Self>,
+            _: &mut core::task::Context<'_>,
+            buf: &mut [u8],
+        ) -> core::task::Poll<io::Result<usize>> {
+            core::task::Poll::Ready(Read::read(self.get_mut(), buf))
+        }
+    }
+
+    #[cfg(feature = "futures")]
+    impl AsyncWrite for FakeServer {
+        fn poll_write(
+            self: core::pin::Pin<&mut Self>,
+            _: &mut core::task::Context<'_>,
+            buf: &[u8],
+        ) -> core::task::Poll<io::Result<usize>> {
+            core::task::Poll::Ready(Write::write(self.get_mut(), buf))
+        }
+
+        fn poll_flush(self: core::pin::Pin<&mut Self>, _: &mut core::task::Context<'_>) -> core::task::Poll<io::Result<()>> {
+            core::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: core::pin::Pin<&mut Self>, _: &mut core::task::Context<'_>) -> core::task::Poll<io::Result<()>> {
+            core::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn test_ping_async() {
+        let mut server = FakeServer::new(test_status());
+        let result = futures_executor::block_on(ping_async(&mut server, "localhost", 25565, 753))
+            .expect("ping succeeds");
+        assert_eq!(result.status, test_status());
+        assert_handshake(&server);
+
+        let mut server = FakeServer::new(test_status());
+        server.pong_offset = -1;
+        match futures_executor::block_on(ping_async(&mut server, "localhost", 25565, 753)) {
+            Err(StatusPingErr::PayloadMismatch { sent, received }) => assert_eq!(sent - 1, received),
+            other => panic!("expected payload mismatch, got {:?}", other),
+        }
+    }
+
+    struct ReadWrite<'a>(&'a mut &'a [u8], Vec<u8>);
+
+    impl Read for ReadWrite<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            Read::read(self.0, buf)
+        }
+    }
+
+    impl Write for ReadWrite<'_> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Write::write(&mut self.1, buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+}
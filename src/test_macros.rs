@@ -13,6 +13,7 @@ macro_rules! packet_test_cases {
                 let mut out = crate::types::BytesSerializer::default();
                 packet.mc_serialize_body(&mut out).expect("serialize succeeds");
                 let bytes = out.into_bytes();
+                assert_eq!(packet.serialized_body_size(), bytes.len(), "serialized_body_size() == serialized body length");
 
                 let raw_packet = $rawnam::create(packet.id(), bytes.as_slice()).expect("valid id");
                 let deserialized = match raw_packet.deserialize() {
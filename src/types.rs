@@ -6,6 +6,10 @@ use crate::uuid::UUID4;
 use crate::*;
 
 pub use super::chat::*;
+pub use super::identifier::*;
+
+mod geometry;
+pub use geometry::*;
 
 #[cfg(all(test, feature = "std"))]
 use crate::protocol::TestRandom;
@@ -16,6 +20,10 @@ impl Serialize for bool {
     fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
         to.serialize_byte(if *self { 1 } else { 0 })
     }
+
+    fn serialized_size(&self) -> usize {
+        1
+    }
 }
 
 impl Deserialize for bool {
@@ -42,6 +50,10 @@ macro_rules! def_primitive {
                 let data = ProtoByteOrder::$write(*self);
                 to.serialize_bytes(&data)
             }
+
+            fn serialized_size(&self) -> usize {
+                core::mem::size_of::<$nam>()
+            }
         }
 
         impl Deserialize for $nam {
@@ -102,6 +114,16 @@ macro_rules! def_varnum {
 
                 to.serialize_bytes(&out[..byte_idx])
             }
+
+            fn serialized_size(&self) -> usize {
+                let mut v: $working_type = self.0 as $working_type;
+                let mut size = 1;
+                while v >= 0x80 {
+                    v >>= 7;
+                    size += 1;
+                }
+                size
+            }
         }
 
         impl Deserialize for $nam {
@@ -183,6 +205,15 @@ impl Serialize for String {
         to.serialize_other(&VarInt(self.len() as i32))?;
         to.serialize_bytes(self.as_bytes())
     }
+
+    fn serialized_size(&self) -> usize {
+        str_serialized_size(self.as_str())
+    }
+}
+
+// the size a string would be serialized as, without needing an owned String
+pub(crate) fn str_serialized_size(value: &str) -> usize {
+    VarInt(value.len() as i32).serialized_size() + value.len()
 }
 
 impl Deserialize for String {
@@ -229,60 +260,112 @@ pub struct IntPosition {
     pub z: i32,
 }
 
-impl Serialize for IntPosition {
-    fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
-        let x_raw = if self.x < 0 {
-            (self.x + 0x2000000) as u64 | 0x2000000
-        } else {
-            self.x as u64
-        } & 0x3FFFFFF;
+// how the 3 coordinates are packed into a long, which changed in 1.14
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PositionLayout {
+    // x (26 bits), z (26 bits), y (12 bits), used since 1.14
+    XZY,
+    // x (26 bits), y (12 bits), z (26 bits), used up to 1.13.2
+    XYZ,
+}
 
-        let z_raw = if self.z < 0 {
-            (self.z + 0x2000000) as u64 | 0x2000000
-        } else {
-            self.z as u64
-        } & 0x3FFFFFF;
+pub const POSITION_LAYOUT_XZY_PROTOCOL_VERSION: i32 = 477;
 
-        let y_raw = if self.y < 0 {
-            (self.y + 0x800) as u64 | 0x800
+impl PositionLayout {
+    pub fn for_protocol_version(protocol_version: i32) -> Self {
+        if protocol_version >= POSITION_LAYOUT_XZY_PROTOCOL_VERSION {
+            PositionLayout::XZY
         } else {
-            self.y as u64
-        } & 0xFFF;
-
-        let data = ProtoByteOrder::write_ulong(((x_raw << 38) | (z_raw << 12) | y_raw) as u64);
-        to.serialize_bytes(&data)
+            PositionLayout::XYZ
+        }
     }
 }
 
-impl Deserialize for IntPosition {
-    fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
-        let Deserialized { value: raw, data } = ProtoByteOrder::read_ulong(data)?;
-        let mut x = ((raw >> 38) as u32) & 0x3FFFFFF;
-        let mut z = ((raw >> 12) & 0x3FFFFFF) as u32;
-        let mut y = ((raw & 0xFFF) as u16) & 0xFFF;
-
-        if (x & 0x2000000) != 0 {
-            // is the 26th bit set
-            // if so, treat the rest as a positive integer, and treat 26th bit as -2^25
-            // 2^25 == 0x2000000
-            // 0x1FFFFFF == 2^26 - 1 (all places set to 1 except 26th place)
-            x = (((x & 0x1FFFFFF) as i32) - 0x2000000) as u32;
+impl IntPosition {
+    pub fn encode(&self, layout: PositionLayout) -> u64 {
+        let x_raw = (self.x as u64) & 0x3FFFFFF;
+        let y_raw = (self.y as u64) & 0xFFF;
+        let z_raw = (self.z as u64) & 0x3FFFFFF;
+
+        match layout {
+            PositionLayout::XZY => (x_raw << 38) | (z_raw << 12) | y_raw,
+            PositionLayout::XYZ => (x_raw << 38) | (y_raw << 26) | z_raw,
         }
-        if (y & 0x800) != 0 {
-            y = (((y & 0x7FF) as i16) - 0x800) as u16;
+    }
+
+    pub fn decode(raw: u64, layout: PositionLayout) -> Self {
+        let (x, y, z) = match layout {
+            PositionLayout::XZY => (raw >> 38, raw & 0xFFF, raw >> 12),
+            PositionLayout::XYZ => (raw >> 38, raw >> 26, raw),
+        };
+
+        IntPosition {
+            x: sign_extend(x, 26) as i32,
+            y: sign_extend(y, 12) as i16,
+            z: sign_extend(z, 26) as i32,
         }
-        if (z & 0x2000000) != 0 {
-            z = (((z & 0x1FFFFFF) as i32) - 0x2000000) as u32;
+    }
+
+    pub fn chunk(&self) -> ChunkPosition<i32> {
+        ChunkPosition { x: self.x >> 4, z: self.z >> 4 }
+    }
+
+    pub fn section(&self) -> ChunkSectionPosition {
+        ChunkSectionPosition {
+            x: self.x >> 4,
+            y: (self.y >> 4) as i32,
+            z: self.z >> 4,
         }
+    }
 
-        Deserialized::ok(
-            IntPosition {
-                x: x as i32,
-                y: y as i16,
-                z: z as i32,
-            },
-            data,
-        )
+    // where this block is inside of its chunk column
+    pub fn horizontal_in_chunk(&self) -> BlockChangeHorizontalPosition {
+        BlockChangeHorizontalPosition {
+            rel_x: (self.x & 0xF) as u8,
+            rel_z: (self.z & 0xF) as u8,
+        }
+    }
+
+    pub fn from_chunk(chunk: ChunkPosition<i32>, horizontal: BlockChangeHorizontalPosition, y: i16) -> Self {
+        IntPosition {
+            x: (chunk.x << 4) | (horizontal.rel_x & 0xF) as i32,
+            y,
+            z: (chunk.z << 4) | (horizontal.rel_z & 0xF) as i32,
+        }
+    }
+}
+
+// the lowest corner of the section
+impl From<ChunkSectionPosition> for IntPosition {
+    fn from(section: ChunkSectionPosition) -> Self {
+        IntPosition {
+            x: section.x << 4,
+            y: (section.y << 4) as i16,
+            z: section.z << 4,
+        }
+    }
+}
+
+// treats the top bit of the lowest `bits` bits as the sign
+fn sign_extend(raw: u64, bits: u32) -> i64 {
+    let shift = 64 - bits;
+    ((raw << shift) as i64) >> shift
+}
+
+impl Serialize for IntPosition {
+    fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
+        let data = ProtoByteOrder::write_ulong(self.encode(PositionLayout::XZY));
+        to.serialize_bytes(&data)
+    }
+
+    fn serialized_size(&self) -> usize {
+        8
+    }
+}
+
+impl Deserialize for IntPosition {
+    fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
+        Ok(ProtoByteOrder::read_ulong(data)?.map(move |raw| IntPosition::decode(raw, PositionLayout::XZY)))
     }
 }
 
@@ -296,6 +379,125 @@ impl TestRandom for IntPosition {
     }
 }
 
+// for protocol modules before 1.14, which use the XYZ layout
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub struct LegacyIntPosition(pub IntPosition);
+
+impl From<IntPosition> for LegacyIntPosition {
+    fn from(position: IntPosition) -> Self {
+        LegacyIntPosition(position)
+    }
+}
+
+impl From<LegacyIntPosition> for IntPosition {
+    fn from(position: LegacyIntPosition) -> Self {
+        position.0
+    }
+}
+
+impl Serialize for LegacyIntPosition {
+    fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
+        let data = ProtoByteOrder::write_ulong(self.0.encode(PositionLayout::XYZ));
+        to.serialize_bytes(&data)
+    }
+
+    fn serialized_size(&self) -> usize {
+        8
+    }
+}
+
+impl Deserialize for LegacyIntPosition {
+    fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
+        Ok(ProtoByteOrder::read_ulong(data)?.map(move |raw| LegacyIntPosition(IntPosition::decode(raw, PositionLayout::XYZ))))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+impl TestRandom for LegacyIntPosition {
+    fn test_gen_random() -> Self {
+        LegacyIntPosition(IntPosition::test_gen_random())
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct BlockChangeHorizontalPosition {
+    pub rel_x: u8,
+    pub rel_z: u8,
+}
+
+impl Serialize for BlockChangeHorizontalPosition {
+    fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
+        to.serialize_byte((self.rel_x & 0xF) << 4 | (self.rel_z & 0xF))
+    }
+
+    fn serialized_size(&self) -> usize {
+        1
+    }
+}
+
+impl Deserialize for BlockChangeHorizontalPosition {
+    fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
+        Ok(
+            u8::mc_deserialize(data)?.map(move |b| BlockChangeHorizontalPosition {
+                rel_x: (b >> 4) & 0xF,
+                rel_z: b & 0xF,
+            }),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+impl TestRandom for BlockChangeHorizontalPosition {
+    fn test_gen_random() -> Self {
+        BlockChangeHorizontalPosition {
+            rel_x: rand::random::<u8>() % 16,
+            rel_z: rand::random::<u8>() % 16,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Copy)]
+pub struct ChunkSectionPosition {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Serialize for ChunkSectionPosition {
+    fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
+        let num = (((self.x as u64) & 0x3FFFFF) << 42)
+            | (((self.z as u64) & 0x3FFFFF) << 20)
+            | ((self.y as u64) & 0xFFFFF);
+
+        to.serialize_other(&num)
+    }
+
+    fn serialized_size(&self) -> usize {
+        8
+    }
+}
+
+impl Deserialize for ChunkSectionPosition {
+    fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
+        let Deserialized { value: raw, data } = u64::mc_deserialize(data)?;
+        let y = sign_extend(raw, 20) as i32;
+        let z = sign_extend(raw >> 20, 22) as i32;
+        let x = sign_extend(raw >> 42, 22) as i32;
+        Deserialized::ok(Self { x, y, z }, data)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+impl TestRandom for ChunkSectionPosition {
+    fn test_gen_random() -> Self {
+        Self {
+            x: sign_extend(u64::test_gen_random(), 22) as i32,
+            y: sign_extend(u64::test_gen_random(), 20) as i32,
+            z: sign_extend(u64::test_gen_random(), 22) as i32,
+        }
+    }
+}
+
 // angle
 #[derive(Copy, Clone, PartialEq, Hash, Debug)]
 pub struct Angle {
@@ -306,6 +508,10 @@ impl Serialize for Angle {
     fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
         to.serialize_byte(self.value)
     }
+
+    fn serialized_size(&self) -> usize {
+        1
+    }
 }
 
 impl Deserialize for Angle {
@@ -330,6 +536,10 @@ impl Serialize for UUID4 {
         let bytes = ProtoByteOrder::write_u2long(self.to_u128());
         to.serialize_bytes(&bytes[..])
     }
+
+    fn serialized_size(&self) -> usize {
+        16
+    }
 }
 
 impl Deserialize for UUID4 {
@@ -341,7 +551,7 @@ impl Deserialize for UUID4 {
 #[cfg(all(test, feature = "std"))]
 impl TestRandom for UUID4 {
     fn test_gen_random() -> Self {
-        UUID4::random()
+        UUID4::random_from(&mut rand::thread_rng())
     }
 }
 
@@ -357,6 +567,10 @@ impl Serialize for NamedNbtTag {
         let bytes = self.root.bytes();
         to.serialize_bytes(bytes.as_slice())
     }
+
+    fn serialized_size(&self) -> usize {
+        self.root.serialized_size()
+    }
 }
 
 impl Deserialize for NamedNbtTag {
@@ -398,6 +612,10 @@ impl Serialize for FixedInt {
     fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
         to.serialize_other(&self.raw)
     }
+
+    fn serialized_size(&self) -> usize {
+        4
+    }
 }
 
 impl Deserialize for FixedInt {
@@ -462,6 +680,13 @@ impl<T> Serialize for Option<T>
             None => to.serialize_other(&false),
         }
     }
+
+    fn serialized_size(&self) -> usize {
+        match self {
+            Some(value) => 1 + value.serialized_size(),
+            None => 1,
+        }
+    }
 }
 
 impl<T> Deserialize for Option<T>
@@ -511,6 +736,10 @@ impl Serialize for ItemStack {
             None => to.serialize_byte(nbt::Tag::End.id()),
         }
     }
+
+    fn serialized_size(&self) -> usize {
+        self.item_id.serialized_size() + 1 + self.nbt.as_ref().map(move |nbt| nbt.serialized_size()).unwrap_or(1)
+    }
 }
 
 impl Deserialize for ItemStack {
@@ -569,6 +798,10 @@ macro_rules! def_vector_type {
                 )+
                 Ok(())
             }
+
+            fn serialized_size(&self) -> usize {
+                0 $(+ self.$fnam.serialized_size())+
+            }
         }
 
         impl<T> Deserialize for $name<T> where T: Deserialize {
@@ -684,6 +917,63 @@ macro_rules! def_vector_type {
             }
         }
 
+        impl<T> core::ops::Add for $name<T> where T: core::ops::Add<Output=T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self { $($fnam: self.$fnam + other.$fnam),+ }
+            }
+        }
+
+        impl<T> core::ops::Sub for $name<T> where T: core::ops::Sub<Output=T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self { $($fnam: self.$fnam - other.$fnam),+ }
+            }
+        }
+
+        impl<T> core::ops::Neg for $name<T> where T: core::ops::Neg<Output=T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($fnam: -self.$fnam),+ }
+            }
+        }
+
+        impl<T> core::ops::AddAssign for $name<T> where T: core::ops::AddAssign {
+            fn add_assign(&mut self, other: Self) {
+                $(self.$fnam += other.$fnam;)+
+            }
+        }
+
+        impl<T> core::ops::SubAssign for $name<T> where T: core::ops::SubAssign {
+            fn sub_assign(&mut self, other: Self) {
+                $(self.$fnam -= other.$fnam;)+
+            }
+        }
+
+        // scales every component
+        impl<T> core::ops::Mul<T> for $name<T> where T: core::ops::Mul<Output=T> + Copy {
+            type Output = Self;
+
+            fn mul(self, by: T) -> Self {
+                Self { $($fnam: self.$fnam * by),+ }
+            }
+        }
+
+        impl<T> $name<T> where T: core::ops::Mul<Output=T> + core::ops::AddAssign + Default + Copy {
+            pub fn dot(&self, other: &Self) -> T {
+                let mut out = T::default();
+                $(out += self.$fnam * other.$fnam;)+
+                out
+            }
+
+            pub fn length_squared(&self) -> T {
+                self.dot(self)
+            }
+        }
+
         impl<T> $name<T> {
             pub fn from_other<O>(other: O) -> Self where O: Into<($(crate::instead_of_ident!($fnam, T)),+)> {
                 let ($($fnam),+) = other.into();
@@ -734,6 +1024,11 @@ impl<E, C> Serialize for CountedArray<E, C> where E: Serialize, C: ArrayCounter
         }
         Ok(())
     }
+
+    fn serialized_size(&self) -> usize {
+        let count = C::from_count(self.data.len());
+        self.data.iter().fold(count.serialized_size(), move |size, elem| size + elem.serialized_size())
+    }
 }
 
 impl<E, C> Deserialize for CountedArray<E, C> where E: Deserialize, C: ArrayCounter {
@@ -847,6 +1142,10 @@ impl Serialize for RemainingBytes {
     fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
         to.serialize_bytes(self.data.as_slice())
     }
+
+    fn serialized_size(&self) -> usize {
+        self.data.len()
+    }
 }
 
 impl Deserialize for RemainingBytes {
@@ -1065,6 +1364,55 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_position_layouts() {
+        // the example from wiki.vg
+        let position = IntPosition { x: 18357644, y: 831, z: -20882616 };
+        assert_eq!(position.encode(PositionLayout::XZY), 0x4607632C15B4833F);
+        assert_eq!(position.encode(PositionLayout::XYZ), 0x4607630CFEC15B48);
+        assert_eq!(IntPosition::decode(0x4607632C15B4833F, PositionLayout::XZY), position);
+        assert_eq!(IntPosition::decode(0x4607630CFEC15B48, PositionLayout::XYZ), position);
+        assert_eq!(PositionLayout::for_protocol_version(404), PositionLayout::XYZ);
+        assert_eq!(PositionLayout::for_protocol_version(578), PositionLayout::XZY);
+
+        // the world border, the limits of each field, and -1 (all bits set)
+        let edges = [
+            IntPosition { x: 29999984, y: 255, z: -29999984 },
+            IntPosition { x: -29999984, y: 0, z: 29999984 },
+            IntPosition { x: 33554431, y: 2047, z: 33554431 },
+            IntPosition { x: -33554432, y: -2048, z: -33554432 },
+            IntPosition { x: -1, y: -1, z: -1 },
+        ];
+        for layout in &[PositionLayout::XZY, PositionLayout::XYZ] {
+            for position in &edges {
+                assert_eq!(IntPosition::decode(position.encode(*layout), *layout), *position, "{:?}", layout);
+            }
+        }
+        assert_eq!(IntPosition { x: -1, y: -1, z: -1 }.encode(PositionLayout::XYZ), u64::MAX);
+
+        for position in &edges {
+            test_type(*position);
+            test_type(LegacyIntPosition(*position));
+        }
+    }
+
+    #[test]
+    fn test_position_chunks() {
+        let position = IntPosition { x: -29999984, y: 70, z: 33 };
+        assert_eq!(position.chunk(), ChunkPosition { x: -1874999, z: 2 });
+        assert_eq!(position.section(), ChunkSectionPosition { x: -1874999, y: 4, z: 2 });
+        let horizontal = position.horizontal_in_chunk();
+        assert_eq!(horizontal, BlockChangeHorizontalPosition { rel_x: 0, rel_z: 1 });
+        assert_eq!(IntPosition::from_chunk(position.chunk(), horizontal, 70), position);
+        assert_eq!(IntPosition::from(position.section()), IntPosition { x: -29999984, y: 64, z: 32 });
+
+        let negative = IntPosition { x: -1, y: -1, z: -17 };
+        assert_eq!(negative.section(), ChunkSectionPosition { x: -1, y: -1, z: -2 });
+        assert_eq!(negative.horizontal_in_chunk(), BlockChangeHorizontalPosition { rel_x: 15, rel_z: 15 });
+        test_type(negative.section());
+        test_type(ChunkSectionPosition { x: 2097151, y: -524288, z: -2097152 });
+    }
+
     #[cfg(feature = "std")]
     #[test]
     fn test_uuid() {
@@ -1089,6 +1437,7 @@ mod tests {
                 .expect("serialization should succeed");
             test.into_bytes()
         };
+        assert_eq!(value.serialized_size(), bytes.len(), "serialized_size() == serialized bytes length");
         let deserialized =
             S::mc_deserialize(bytes.as_slice()).expect("deserialization should succeed");
         assert!(deserialized.data.is_empty());
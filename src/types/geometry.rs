@@ -0,0 +1,295 @@
+use super::*;
+
+// entity movement packets send the change in position as a short in 1/4096ths of a block
+pub const ENTITY_DELTA_SCALE: f64 = 4096.0;
+
+impl Angle {
+    // 256 steps per full turn, rounding down like vanilla does
+    pub fn from_degrees(degrees: f32) -> Self {
+        Angle {
+            value: floor_f64((degrees as f64) * 256.0 / 360.0) as i64 as u8,
+        }
+    }
+
+    pub fn from_radians(radians: f32) -> Self {
+        Self::from_degrees(radians.to_degrees())
+    }
+
+    // the value is read as signed, so this is between -180 and 180
+    pub fn to_degrees(self) -> f32 {
+        (self.value as i8) as f32 * 360.0 / 256.0
+    }
+
+    pub fn to_radians(self) -> f32 {
+        self.to_degrees().to_radians()
+    }
+}
+
+impl EntityRotation<f32> {
+    pub fn to_angles(self) -> EntityRotation<Angle> {
+        EntityRotation {
+            yaw: Angle::from_degrees(self.yaw),
+            pitch: Angle::from_degrees(self.pitch),
+        }
+    }
+
+    // the unit vector an entity with this yaw and pitch (in degrees) is looking along
+    #[cfg(feature = "std")]
+    pub fn look_vector(&self) -> Vec3<f64> {
+        let yaw = (self.yaw as f64).to_radians();
+        let pitch = (self.pitch as f64).to_radians();
+        Vec3 {
+            x: -yaw.sin() * pitch.cos(),
+            y: -pitch.sin(),
+            z: yaw.cos() * pitch.cos(),
+        }
+    }
+
+    // the yaw and pitch needed to look along direction, which doesn't have to be normalized
+    #[cfg(feature = "std")]
+    pub fn from_look_vector(direction: &Vec3<f64>) -> Self {
+        let horizontal = (direction.x * direction.x + direction.z * direction.z).sqrt();
+        EntityRotation {
+            yaw: (-direction.x).atan2(direction.z).to_degrees() as f32,
+            pitch: (-direction.y).atan2(horizontal).to_degrees() as f32,
+        }
+    }
+}
+
+impl EntityRotation<Angle> {
+    pub fn to_degrees(self) -> EntityRotation<f32> {
+        EntityRotation {
+            yaw: self.yaw.to_degrees(),
+            pitch: self.pitch.to_degrees(),
+        }
+    }
+}
+
+impl Vec3<f64> {
+    #[cfg(feature = "std")]
+    pub fn length(&self) -> f64 {
+        self.length_squared().sqrt()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn distance(&self, other: &Self) -> f64 {
+        (*other - *self).length()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn normalized(&self) -> Self {
+        let length = self.length();
+        if length == 0.0 {
+            *self
+        } else {
+            *self * (1.0 / length)
+        }
+    }
+
+    // the block this point is inside of
+    pub fn block(&self) -> IntPosition {
+        IntPosition {
+            x: floor_f64(self.x) as i32,
+            y: floor_f64(self.y) as i16,
+            z: floor_f64(self.z) as i32,
+        }
+    }
+
+    pub fn chunk(&self) -> ChunkPosition<i32> {
+        self.block().chunk()
+    }
+
+    pub fn section(&self) -> ChunkSectionPosition {
+        self.block().section()
+    }
+
+    // the delta an entity position packet needs to move from self to other, or None if it's too far (the entity
+    // must be teleported instead)
+    pub fn entity_delta_to(&self, other: &Self) -> Option<Vec3<i16>> {
+        let delta = |from: f64, to: f64| {
+            let change = entity_delta_units(to)?.checked_sub(entity_delta_units(from)?)?;
+            if change < i16::MIN as i64 || change > i16::MAX as i64 {
+                None
+            } else {
+                Some(change as i16)
+            }
+        };
+
+        Some(Vec3 {
+            x: delta(self.x, other.x)?,
+            y: delta(self.y, other.y)?,
+            z: delta(self.z, other.z)?,
+        })
+    }
+
+    pub fn with_entity_delta(&self, delta: &Vec3<i16>) -> Self {
+        // positions too large (or not finite) to have a delta applied are left alone
+        let apply = |from: f64, change: i16| match entity_delta_units(from) {
+            Some(units) => (units.saturating_add(change as i64) as f64) / ENTITY_DELTA_SCALE,
+            None => from,
+        };
+
+        Vec3 {
+            x: apply(self.x, delta.x),
+            y: apply(self.y, delta.y),
+            z: apply(self.z, delta.z),
+        }
+    }
+}
+
+impl Vec3<FixedInt> {
+    pub fn from_f64(position: &Vec3<f64>, fractional_bytes: usize) -> Self {
+        Vec3 {
+            x: FixedInt::new(position.x, fractional_bytes),
+            y: FixedInt::new(position.y, fractional_bytes),
+            z: FixedInt::new(position.z, fractional_bytes),
+        }
+    }
+
+    pub fn into_f64(self, fractional_bytes: usize) -> Vec3<f64> {
+        Vec3 {
+            x: self.x.into_float(fractional_bytes),
+            y: self.y.into_float(fractional_bytes),
+            z: self.z.into_float(fractional_bytes),
+        }
+    }
+}
+
+// None when the position isn't finite, or is too far out to count in 1/4096ths with an i64
+fn entity_delta_units(position: f64) -> Option<i64> {
+    const LIMIT: f64 = 9223372036854775808.0; // 2^63
+
+    let units = floor_f64(position * ENTITY_DELTA_SCALE);
+    if (-LIMIT..LIMIT).contains(&units) {
+        Some(units as i64)
+    } else {
+        None
+    }
+}
+
+// f64::floor needs std
+fn floor_f64(value: f64) -> f64 {
+    // from 2^52 up every f64 is a whole number already, this also passes nan and the infinities through
+    if !value.is_finite() || value.abs() >= 4503599627370496.0 {
+        return value;
+    }
+
+    let truncated = value as i64 as f64;
+    if truncated > value {
+        truncated - 1.0
+    } else {
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_ops() {
+        let a = Vec3 { x: 1, y: 2, z: 3 };
+        let b = Vec3 { x: -4, y: 5, z: 6 };
+        assert_eq!(a + b, Vec3 { x: -3, y: 7, z: 9 });
+        assert_eq!(a - b, Vec3 { x: 5, y: -3, z: -3 });
+        assert_eq!(-a, Vec3 { x: -1, y: -2, z: -3 });
+        assert_eq!(a * 3, Vec3 { x: 3, y: 6, z: 9 });
+        assert_eq!(a.dot(&b), 24);
+        assert_eq!(a.length_squared(), 14);
+
+        let mut c = ChunkPosition { x: 1, z: 1 };
+        c += ChunkPosition { x: 2, z: -3 };
+        c -= ChunkPosition { x: 1, z: 1 };
+        assert_eq!(c, ChunkPosition { x: 2, z: -3 });
+    }
+
+    #[test]
+    fn test_angles() {
+        assert_eq!(Angle::from_degrees(90.0).value, 64);
+        assert_eq!(Angle::from_degrees(-90.0).value, 192);
+        assert_eq!(Angle::from_degrees(359.0).value, 255);
+        assert_eq!(Angle::from_degrees(360.0).value, 0);
+        assert_eq!(Angle { value: 192 }.to_degrees(), -90.0);
+        assert_eq!(Angle { value: 32 }.to_degrees(), 45.0);
+
+        let rotation = EntityRotation { yaw: 45.0f32, pitch: -90.0 };
+        assert_eq!(rotation.to_angles(), EntityRotation { yaw: Angle { value: 32 }, pitch: Angle { value: 192 } });
+        assert_eq!(rotation.to_angles().to_degrees(), rotation);
+    }
+
+    #[test]
+    fn test_block_coordinates() {
+        let position = Vec3 { x: -0.5, y: 64.99, z: 17.0 };
+        assert_eq!(position.block(), IntPosition { x: -1, y: 64, z: 17 });
+        assert_eq!(position.chunk(), ChunkPosition { x: -1, z: 1 });
+        assert_eq!(position.section(), ChunkSectionPosition { x: -1, y: 4, z: 1 });
+    }
+
+    #[test]
+    fn test_entity_delta() {
+        let from = Vec3 { x: 10.0, y: 64.0, z: -3.5 };
+        let to = Vec3 { x: 10.5, y: 63.0, z: -3.25 };
+        let delta = from.entity_delta_to(&to).expect("close enough to move");
+        assert_eq!(delta, Vec3 { x: 2048, y: -4096, z: 1024 });
+        assert_eq!(from.with_entity_delta(&delta), to);
+
+        // 8 blocks is the most a single move can go (less 1/4096)
+        assert!(from.entity_delta_to(&(from + Vec3 { x: 7.999, y: 0.0, z: 0.0 })).is_some());
+        assert!(from.entity_delta_to(&(from + Vec3 { x: 8.0, y: 0.0, z: 0.0 })).is_none());
+        assert!(from.entity_delta_to(&(from + Vec3 { x: 0.0, y: -8.0, z: 0.0 })).is_some());
+        assert!(from.entity_delta_to(&(from + Vec3 { x: 0.0, y: 0.0, z: -8.001 })).is_none());
+    }
+
+    #[test]
+    fn test_non_finite_and_huge() {
+        assert_eq!(Angle::from_degrees(f32::NEG_INFINITY), Angle { value: 0 });
+        assert_eq!(Angle::from_degrees(f32::INFINITY), Angle { value: 255 });
+        assert_eq!(Angle::from_degrees(f32::NAN), Angle { value: 0 });
+        assert_eq!(Angle::from_degrees(-90.0), Angle { value: 192 });
+
+        // block coordinates saturate instead of wrapping
+        assert_eq!(Vec3 { x: -1e20, y: 1e300, z: f64::NAN }.block(), IntPosition { x: i32::MIN, y: i16::MAX, z: 0 });
+        assert_eq!(Vec3 { x: f64::INFINITY, y: f64::NEG_INFINITY, z: -1e300 }.block(), IntPosition { x: i32::MAX, y: i16::MIN, z: i32::MIN });
+
+        let origin = Vec3 { x: 0.0, y: 0.0, z: 0.0 };
+        let far = |x: f64| Vec3 { x, y: 0.0, z: 0.0 };
+        assert!(far(-1e300).entity_delta_to(&far(1e300)).is_none());
+        assert!(far(1e300).entity_delta_to(&far(1e300)).is_none());
+        assert!(origin.entity_delta_to(&far(f64::NAN)).is_none());
+        assert!(origin.entity_delta_to(&far(f64::INFINITY)).is_none());
+        assert!(far(f64::NEG_INFINITY).entity_delta_to(&origin).is_none());
+        assert!(far(f64::NAN).entity_delta_to(&far(f64::NAN)).is_none());
+
+        let delta = Vec3 { x: 4096, y: 0, z: 0 };
+        assert_eq!(far(1e300).with_entity_delta(&delta).x, 1e300);
+        assert_eq!(far(-1e300).with_entity_delta(&delta).x, -1e300);
+        assert_eq!(far(f64::INFINITY).with_entity_delta(&delta).x, f64::INFINITY);
+        assert!(far(f64::NAN).with_entity_delta(&delta).x.is_nan());
+        assert_eq!(origin.with_entity_delta(&delta).x, 1.0);
+    }
+
+    #[test]
+    fn test_fixed_int_vectors() {
+        let position = Vec3 { x: 1.5, y: -2.25, z: 100.0 };
+        let fixed = <Vec3<FixedInt>>::from_f64(&position, 3);
+        assert_eq!(fixed.x, FixedInt::new(1.5, 3));
+        assert_eq!(fixed.into_f64(3), position);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_look_vector() {
+        let close = |a: &Vec3<f64>, b: &Vec3<f64>| (*a - *b).length() < 1e-9;
+        let look = |yaw: f32, pitch: f32| EntityRotation { yaw, pitch }.look_vector();
+        assert!(close(&look(0.0, 0.0), &Vec3 { x: 0.0, y: 0.0, z: 1.0 }));
+        assert!(close(&look(90.0, 0.0), &Vec3 { x: -1.0, y: 0.0, z: 0.0 }));
+        assert!(close(&look(0.0, -90.0), &Vec3 { x: 0.0, y: 1.0, z: 0.0 }));
+
+        let direction = Vec3 { x: 3.0, y: -4.0, z: -3.0 };
+        let rotation = EntityRotation::from_look_vector(&direction);
+        assert!((rotation.yaw - -135.0).abs() < 1e-4);
+        assert!((rotation.look_vector() - direction.normalized()).length() < 1e-6);
+        assert!((direction.length() - 34f64.sqrt()).abs() < 1e-12);
+        assert_eq!(Vec3 { x: 0.0, y: 0.0, z: 0.0 }.distance(&Vec3 { x: 3.0, y: 4.0, z: 0.0 }), 5.0);
+    }
+}
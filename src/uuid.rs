@@ -1,8 +1,12 @@
 use crate::utils::*;
+use crate::nbt::{NamedTag, Tag};
 use serde::{Deserializer, Serializer};
-use alloc::{fmt, string::{ToString, String}};
+use rand_core::RngCore;
+use alloc::{fmt, string::{ToString, String}, vec, vec::Vec, format};
 use fmt::{Display, Debug, Formatter};
 
+mod md5;
+
 #[derive(Copy, Clone, PartialEq, Hash, Eq)]
 pub struct UUID4 {
     raw: u128,
@@ -22,12 +26,28 @@ impl Debug for UUID4 {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum UUIDVariant {
+    Ncs,
+    // the only one minecraft uses
+    Rfc4122,
+    Microsoft,
+    Reserved,
+}
+
 impl From<u128> for UUID4 {
     fn from(raw: u128) -> Self {
         UUID4 { raw }
     }
 }
 
+// the 1.16+ encoding, for the older ones see to_most_least and hex
+impl From<UUID4> for Tag {
+    fn from(id: UUID4) -> Self {
+        Tag::IntArray(id.to_int_array().to_vec())
+    }
+}
+
 impl serde::Serialize for UUID4 {
     fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
     where
@@ -73,11 +93,109 @@ impl UUID4 {
 
     #[cfg(feature = "std")]
     pub fn random() -> Self {
+        Self::random_from(&mut rand::thread_rng())
+    }
+
+    pub fn random_from<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        let mut bytes = [0u8; 16];
+        rng.fill_bytes(&mut bytes);
+        Self::from_random_bytes(bytes)
+    }
+
+    // keeps 122 of the bits, the rest mark it as a version 4 (random) uuid
+    pub fn from_random_bytes(mut bytes: [u8; 16]) -> Self {
+        bytes[6] = (bytes[6] & 0x0F) | 0x40;
+        bytes[8] = (bytes[8] & 0x3F) | 0x80;
         UUID4 {
-            raw: rand::random(),
+            raw: u128::from_be_bytes(bytes),
         }
     }
 
+    // what an offline mode (or bungeecord forwarded without ip forwarding) server assigns to a player with this name
+    pub fn offline(name: &str) -> Self {
+        let mut data = String::from("OfflinePlayer:");
+        data.push_str(name);
+        Self::name_based(data.as_bytes())
+    }
+
+    // a version 3 uuid, the same as java's UUID.nameUUIDFromBytes
+    pub fn name_based(data: &[u8]) -> Self {
+        let mut bytes = md5::md5(data);
+        bytes[6] = (bytes[6] & 0x0F) | 0x30;
+        bytes[8] = (bytes[8] & 0x3F) | 0x80;
+        UUID4 {
+            raw: u128::from_be_bytes(bytes),
+        }
+    }
+
+    // 4 for a mojang account (random), 3 for offline players (name based)
+    pub fn version(self) -> u8 {
+        ((self.raw >> 76) & 0xF) as u8
+    }
+
+    pub fn variant(self) -> UUIDVariant {
+        let bits = ((self.raw >> 61) & 0x7) as u8;
+        if bits & 0b100 == 0 {
+            UUIDVariant::Ncs
+        } else if bits & 0b010 == 0 {
+            UUIDVariant::Rfc4122
+        } else if bits & 0b001 == 0 {
+            UUIDVariant::Microsoft
+        } else {
+            UUIDVariant::Reserved
+        }
+    }
+
+    // most significant int first, how entities and items store uuids since 1.16
+    pub fn to_int_array(self) -> [i32; 4] {
+        let raw = self.raw;
+        [(raw >> 96) as i32, (raw >> 64) as i32, (raw >> 32) as i32, raw as i32]
+    }
+
+    pub fn from_int_array(ints: &[i32]) -> Option<Self> {
+        match ints {
+            [a, b, c, d] => Some(UUID4 {
+                raw: ((*a as u32 as u128) << 96) | ((*b as u32 as u128) << 64) | ((*c as u32 as u128) << 32) | (*d as u32 as u128),
+            }),
+            _ => None,
+        }
+    }
+
+    // like java's getMostSignificantBits and getLeastSignificantBits
+    pub fn to_most_least(self) -> (i64, i64) {
+        ((self.raw >> 64) as i64, self.raw as i64)
+    }
+
+    pub fn from_most_least(most: i64, least: i64) -> Self {
+        UUID4 {
+            raw: ((most as u64 as u128) << 64) | (least as u64 as u128),
+        }
+    }
+
+    // accepts an int array, or a string (dashed or not) as written by some older versions
+    pub fn from_nbt(tag: &Tag) -> Option<Self> {
+        match tag {
+            Tag::IntArray(ints) => Self::from_int_array(ints.as_slice()),
+            Tag::String(str) => Self::parse(str.as_str()),
+            _ => None,
+        }
+    }
+
+    // reads the pre 1.16 pair of longs from a compound, prefix is "UUID" for UUIDMost and UUIDLeast
+    pub fn from_nbt_most_least(compound: &Tag, prefix: &str) -> Option<Self> {
+        let most = compound.get_i64(format!("{}Most", prefix).as_str())?;
+        let least = compound.get_i64(format!("{}Least", prefix).as_str())?;
+        Some(Self::from_most_least(most, least))
+    }
+
+    pub fn to_nbt_most_least(self, prefix: &str) -> Vec<NamedTag> {
+        let (most, least) = self.to_most_least();
+        vec![
+            Tag::Long(most).with_name(format!("{}Most", prefix).as_str()),
+            Tag::Long(least).with_name(format!("{}Least", prefix).as_str()),
+        ]
+    }
+
     pub fn to_u128(self) -> u128 {
         self.raw
     }
@@ -102,6 +220,11 @@ struct RawUUID<'a> {
 impl<'a> RawUUID<'a> {
     fn from_str(from: &'a str) -> Option<RawUUID<'a>> {
         const DASH: &'static str = "-";
+        // every valid uuid is ascii, checking up front means the splits below always land on char boundaries
+        if !from.is_ascii() {
+            return None;
+        }
+
         // 8-4-4-4-12
         // with or without dashes but must be consistent
         let (s0, mut from) = str_split(from, 8)?;
@@ -193,7 +316,7 @@ fn str_check_hex(mut source: &str) -> Option<()> {
 }
 
 fn str_split(source: &str, n: usize) -> Option<(&str, &str)> {
-    if source.len() < n {
+    if source.len() < n || !source.is_char_boundary(n) {
         None
     } else {
         Some(source.split_at(n))
@@ -202,14 +325,52 @@ fn str_split(source: &str, n: usize) -> Option<(&str, &str)> {
 
 #[cfg(test)]
 mod tests {
-    use super::UUID4;
+    use super::{UUID4, UUIDVariant};
+    use crate::nbt::Tag;
+    use alloc::vec;
     #[cfg(feature = "std")]
     use alloc::string::ToString;
 
     #[cfg(feature = "std")]
     #[test]
     fn test_random_uuid4() {
-        UUID4::random();
+        let id = UUID4::random();
+        assert_eq!(id.version(), 4);
+        assert_eq!(id.variant(), UUIDVariant::Rfc4122);
+    }
+
+    // a fixed sequence, like an embedded device's hardware rng would hand out
+    struct CountingRng(u8);
+
+    impl rand_core::RngCore for CountingRng {
+        fn next_u32(&mut self) -> u32 {
+            rand_core::impls::next_u32_via_fill(self)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            rand_core::impls::next_u64_via_fill(self)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest {
+                *byte = self.0;
+                self.0 = self.0.wrapping_add(0x11);
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_uuid4_random_from() {
+        let id = UUID4::random_from(&mut CountingRng(0));
+        assert_eq!(id.hex().as_str(), "00112233-4455-4677-8899-aabbccddeeff");
+        assert_eq!(id.version(), 4);
+        assert_eq!(id.variant(), UUIDVariant::Rfc4122);
+        assert_eq!(UUID4::from_random_bytes([0xFF; 16]).hex().as_str(), "ffffffff-ffff-4fff-bfff-ffffffffffff");
     }
 
     const VALID_UUID: &str = "e1cde35a-0758-47f6-adf8-9dcb44884e5d";
@@ -242,6 +403,71 @@ mod tests {
         assert_eq!(uuid_a, uuid_b);
     }
 
+    #[test]
+    fn test_offline_uuid() {
+        let notch = UUID4::offline("Notch");
+        assert_eq!(notch.hex().as_str(), "b50ad385-829d-3141-a216-7e7d7539ba7f");
+        assert_eq!(notch.version(), 3);
+        assert_eq!(notch.variant(), UUIDVariant::Rfc4122);
+        assert_ne!(UUID4::offline("notch"), notch);
+
+        let online = UUID4::parse(VALID_UUID).expect("should parse valid uuid correctly");
+        assert_eq!(online.version(), 4);
+        assert_eq!(online.variant(), UUIDVariant::Rfc4122);
+        assert_eq!(UUID4::from(0).variant(), UUIDVariant::Ncs);
+        assert_eq!(UUID4::from(u128::MAX).variant(), UUIDVariant::Reserved);
+    }
+
+    #[test]
+    fn test_uuid4_nbt_invalid() {
+        assert_eq!(UUID4::from_nbt(&Tag::IntArray(vec![])), None);
+        assert_eq!(UUID4::from_nbt(&Tag::IntArray(vec![1, 2, 3, 4, 5])), None);
+        assert_eq!(UUID4::from_nbt(&Tag::String("".into())), None);
+        assert_eq!(UUID4::from_nbt(&Tag::String("069a79f4-44e94726-a5be-fca90e38aaf5".into())), None);
+        assert_eq!(UUID4::from_nbt(&Tag::String("069a79f4-44e9-4726-a5be-fca90e38aaf".into())), None);
+        assert_eq!(UUID4::from_nbt(&Tag::String("069a79f4-44e9-4726-a5be-fca90e38aaf5!".into())), None);
+        assert_eq!(UUID4::from_nbt(&Tag::String("g69a79f444e94726a5befca90e38aaf5".into())), None);
+        assert_eq!(UUID4::from_nbt(&Tag::String("aaaaaaaéaaaaaaaaaaaaaaaaaaaaaa".into())), None);
+        assert_eq!(UUID4::from_nbt(&Tag::String("069a79f444e94726a5befca90e38aaé".into())), None);
+        assert_eq!(UUID4::from_nbt(&Tag::LongArray(vec![1, 2])), None);
+        assert_eq!(UUID4::from_nbt(&Tag::Compound(Vec::new())), None);
+
+        // both halves have to be there, and have to be longs
+        let only_most = Tag::Compound(vec![Tag::Long(1).with_name("UUIDMost")]);
+        assert_eq!(UUID4::from_nbt_most_least(&only_most, "UUID"), None);
+        let ints = Tag::Compound(vec![Tag::Int(1).with_name("UUIDMost"), Tag::Int(2).with_name("UUIDLeast")]);
+        assert_eq!(UUID4::from_nbt_most_least(&ints, "UUID"), None);
+        assert_eq!(UUID4::from_nbt_most_least(&Tag::Long(1), "UUID"), None);
+    }
+
+    #[test]
+    fn test_uuid4_parse_non_ascii() {
+        // the multi-byte char straddles the first split, this used to panic
+        assert!(UUID4::parse("aaaaaaaéaaaaaaaaaaaaaaaaaaaaaa").is_none());
+        assert!(UUID4::parse("e1cde35a-0758-47f6-adf8-9dcb44884eé").is_none());
+        assert!(UUID4::parse("é1cde35a075847f6adf89dcb44884e5d").is_none());
+    }
+
+    #[test]
+    fn test_uuid4_nbt() {
+        // Notch, as written by 1.16.3 and 1.15.2
+        let id = UUID4::parse("069a79f4-44e9-4726-a5be-fca90e38aaf5").expect("should parse valid uuid correctly");
+        let ints = [110787060, 1156138790, -1514210135, 238594805];
+        assert_eq!(id.to_int_array(), ints);
+        assert_eq!(Tag::from(id), Tag::IntArray(ints.to_vec()));
+        assert_eq!(UUID4::from_nbt(&Tag::IntArray(ints.to_vec())), Some(id));
+        assert_eq!(UUID4::from_nbt(&Tag::IntArray(vec![1, 2, 3])), None);
+        assert_eq!(UUID4::from_nbt(&Tag::String("069a79f444e94726a5befca90e38aaf5".into())), Some(id));
+        assert_eq!(UUID4::from_nbt(&Tag::Long(1)), None);
+
+        let (most, least) = id.to_most_least();
+        assert_eq!((most, least), (475826800676128550, -6503483008858150155));
+        let compound = Tag::Compound(id.to_nbt_most_least("UUID"));
+        assert_eq!(compound.get_i64("UUIDMost"), Some(most));
+        assert_eq!(UUID4::from_nbt_most_least(&compound, "UUID"), Some(id));
+        assert_eq!(UUID4::from_nbt_most_least(&compound, "Owner"), None);
+    }
+
     #[cfg(feature = "std")]
     #[test]
     fn test_random_uuid4_hex() {
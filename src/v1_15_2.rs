@@ -1,4 +1,4 @@
-use crate::{types::*, uuid::*, *};
+use crate::{types::*, uuid::*, profile::*, *};
 use alloc::{string::{String, ToString}, vec::Vec, borrow::ToOwned, boxed::Box};
 use alloc::fmt;
 use fmt::Debug;
@@ -29,7 +29,7 @@ define_protocol!(578, Packet578, RawPacket578, RawPacket578Body, Packet578Kind =
 
     // login
     LoginDisconnect, 0x00, Login, ClientBound => LoginDisconnectSpec {
-        message: Chat
+        message: LegacyChat
     },
     LoginEncryptionRequest, 0x01, Login, ClientBound => LoginEncryptionRequestSpec {
         server_id: String,
@@ -45,7 +45,7 @@ define_protocol!(578, Packet578, RawPacket578, RawPacket578Body, Packet578Kind =
     },
     LoginPluginRequest, 0x04, Login, ClientBound => LoginPluginRequestSpec {
         message_id: VarInt,
-        channel: String,
+        channel: Identifier,
         data: RemainingBytes
     },
     LoginStart, 0x00, Login, ServerBound => LoginStartSpec {
@@ -145,7 +145,7 @@ define_protocol!(578, Packet578, RawPacket578, RawPacket578Body, Packet578Kind =
         locked: bool
     },
     PlayServerChatMessage, 0x0F, Play, ClientBound => PlayServerChatMessageSpec {
-        message: Chat,
+        message: LegacyChat,
         position: ChatPosition
     },
     PlayMultiBlockChange, 0x10, Play, ClientBound => PlayMultiBlockChangeSpec {
@@ -189,18 +189,18 @@ define_protocol!(578, Packet578, RawPacket578, RawPacket578Body, Packet578Kind =
         cooldown_ticks: VarInt
     },
     PlayServerPluginMessage, 0x19, Play, ClientBound => PlayServerPluginMessageSpec {
-        channel: String,
+        channel: Identifier,
         data: RemainingBytes
     },
     PlayNamedSoundEffect, 0x1A, Play, ClientBound => PlayNamedSoundEffectSpec {
-        sound_name: String,
+        sound_name: Identifier,
         sound_category: SoundCategory,
         position: Vec3<FixedInt>,
         volume: f32,
         pitch: f32
     },
     PlayDisconnect, 0x1B, Play, ClientBound => PlayDisconnectSpec {
-        reason: Chat
+        reason: LegacyChat
     },
     PlayEntityStatus, 0x1C, Play, ClientBound => PlayEntityStatusSpec {
         entity_id: i32,
@@ -308,7 +308,7 @@ define_protocol!(578, Packet578, RawPacket578, RawPacket578Body, Packet578Kind =
     },
     PlayCraftRecipeResponse, 0x31, Play, ClientBound => PlayCraftRecipeResponseSpec {
         window_id: u8,
-        recipe: String
+        recipe: Identifier
     },
     PlayServerPlayerAbilities, 0x32, Play, ClientBound => PlayServerPlayerAbilitiesSpec {
         flags: PlayerAbilityFlags,
@@ -337,7 +337,7 @@ define_protocol!(578, Packet578, RawPacket578, RawPacket578Body, Packet578Kind =
         crafting_book_active: bool,
         smelting_book_open: bool,
         smelting_book_active: bool,
-        recipe_ids: CountedArray<String, VarInt>,
+        recipe_ids: CountedArray<Identifier, VarInt>,
         other_recipe_ids: RemainingBytes // todo
     },
     PlayDestroyEntities, 0x38, Play, ClientBound => PlayDestroyEntitiesSpec {
@@ -362,7 +362,7 @@ define_protocol!(578, Packet578, RawPacket578, RawPacket578Body, Packet578Kind =
         head_yaw: Angle
     },
     PlaySelectAdvancementTab, 0x3D, Play, ClientBound => PlaySelectAdvancementTabSpec {
-        identifier: Option<String>
+        identifier: Option<Identifier>
     },
     PlayWorldBorder, 0x3E, Play, ClientBound => PlayWorldBorderSpec {
         action: WorldBorderAction
@@ -454,8 +454,8 @@ define_protocol!(578, Packet578, RawPacket578, RawPacket578Body, Packet578Kind =
         spec: StopSoundSpec
     },
     PlayerPlayerListHeaderAndFooter, 0x54, Play, ClientBound => PlayPlayerListHeaderAndFooterSpec {
-        header: Chat,
-        footer: Chat
+        header: LegacyChat,
+        footer: LegacyChat
     },
     PlayNbtQueryResponse, 0x55, Play, ClientBound => PlayNbtQueryResponseSpec {
         transaction_id: VarInt,
@@ -474,7 +474,7 @@ define_protocol!(578, Packet578, RawPacket578, RawPacket578Body, Packet578Kind =
     PlayAdvancements, 0x58, Play, ClientBound => PlayAdvancementsSpec {
         reset: bool,
         mappings: CountedArray<AdvancementMappingEntrySpec, VarInt>,
-        identifiers: CountedArray<String, VarInt>,
+        identifiers: CountedArray<Identifier, VarInt>,
         progress: CountedArray<AdvancementProgressEntrySpec, VarInt>
     },
     PlayEntityProperties, 0x59, Play, ClientBound => PlayEntityPropertiesSpec {
@@ -552,7 +552,7 @@ define_protocol!(578, Packet578, RawPacket578, RawPacket578Body, Packet578Kind =
         window_id: u8
     },
     PlayClientPluginMessage, 0x0B, Play, ServerBound => PlayClientPluginMessageSpec {
-        channel: String,
+        channel: Identifier,
         data: RemainingBytes
     },
     PlayEditBook, 0x0C, Play, ServerBound => PlayEditBookSpec {
@@ -597,7 +597,7 @@ define_protocol!(578, Packet578, RawPacket578, RawPacket578Body, Packet578Kind =
     },
     PlayCraftRecipeRequest, 0x18, Play, ServerBound => PlayCraftRecipeRequestSpec {
         window_id: i8,
-        recipe: String,
+        recipe: Identifier,
         make_all: bool
     },
     PlayClientPlayerAbilities, 0x19, Play, ServerBound => PlayClientPlayerAbilitiesSpec {
@@ -659,8 +659,8 @@ define_protocol!(578, Packet578, RawPacket578, RawPacket578Body, Packet578Kind =
     },
     PlayUpdateJigsawBlock, 0x27, Play, ServerBound => PlayUpdateJigsawBlockSpec {
         location: IntPosition,
-        attachment_type: String,
-        target_pool: String,
+        attachment_type: Identifier,
+        target_pool: Identifier,
         final_state: String
     },
     PlayUpdateStructureBlock, 0x28, Play, ServerBound => PlayUpdateStructureBlockSpec {
@@ -831,38 +831,7 @@ proto_byte_enum!(ChatPosition,
     0x02 :: Hotbar
 );
 
-#[derive(Copy, Clone, PartialEq, Debug)]
-pub struct BlockChangeHorizontalPosition {
-    pub rel_x: u8,
-    pub rel_z: u8,
-}
-
-impl Serialize for BlockChangeHorizontalPosition {
-    fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
-        to.serialize_byte((self.rel_x & 0xF) << 4 | (self.rel_z & 0xF))
-    }
-}
-
-impl Deserialize for BlockChangeHorizontalPosition {
-    fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
-        Ok(
-            u8::mc_deserialize(data)?.map(move |b| BlockChangeHorizontalPosition {
-                rel_x: (b >> 4) & 0xF,
-                rel_z: b & 0xF,
-            }),
-        )
-    }
-}
-
-#[cfg(all(test, feature = "std"))]
-impl TestRandom for BlockChangeHorizontalPosition {
-    fn test_gen_random() -> Self {
-        BlockChangeHorizontalPosition {
-            rel_x: rand::random::<u8>() % 16,
-            rel_z: rand::random::<u8>() % 16,
-        }
-    }
-}
+pub use crate::types::{BlockChangeHorizontalPosition, ChunkSectionPosition};
 
 proto_struct!(MultiBlockChangeRecord {
     horizontal_position: BlockChangeHorizontalPosition,
@@ -904,7 +873,7 @@ proto_byte_flag!(BossBarFlags,
 );
 
 proto_struct!(BossBarAddSpec {
-    title: Chat,
+    title: LegacyChat,
     health: f32,
     color: BossBarColor,
     division: BossBarDivision,
@@ -926,7 +895,7 @@ proto_struct!(BossBarUpdateFlagsSpec {
 
 proto_struct!(TabCompleteMatch {
     match_: String,
-    tooltip: Option<Chat>
+    tooltip: Option<LegacyChat>
 });
 
 #[derive(Clone, Debug, PartialEq)]
@@ -1328,7 +1297,7 @@ impl TestRandom for TeamMember {
         if rand_bool {
             Player(String::test_gen_random())
         } else {
-            Entity(UUID4::random())
+            Entity(UUID4::test_gen_random())
         }
     }
 }
@@ -1352,24 +1321,24 @@ proto_struct!(TeamActionPlayerList {
 });
 
 proto_struct!(TeamActionCreateSpec {
-    display_name: Chat,
+    display_name: LegacyChat,
     friendly_flags: TeamFriendlyFlags,
     tag_name_visibility: TeamTagNameVisibility,
     collision_rule: TeamCollisionRule,
     color: VarInt,
-    prefix: Chat,
-    suffix: Chat,
+    prefix: LegacyChat,
+    suffix: LegacyChat,
     entities: CountedArray<TeamMember, VarInt>
 });
 
 proto_struct!(TeamActionUpdateInfoSpec {
-    display_name: Chat,
+    display_name: LegacyChat,
     friendly_flags: TeamFriendlyFlags,
     tag_name_visibility: TeamTagNameVisibility,
     collision_rule: TeamCollisionRule,
     color: VarInt,
-    prefix: Chat,
-    suffix: Chat
+    prefix: LegacyChat,
+    suffix: LegacyChat
 });
 
 proto_byte_flag!(TeamFriendlyFlags,
@@ -1423,9 +1392,9 @@ impl TestRandom for UpdateScoreSpec {
 }
 
 proto_varint_enum!(TitleActionSpec,
-    0x00 :: SetTitle(Chat),
-    0x01 :: SetSubtitle(Chat),
-    0x02 :: SetActionBar(Chat),
+    0x00 :: SetTitle(LegacyChat),
+    0x01 :: SetSubtitle(LegacyChat),
+    0x02 :: SetActionBar(LegacyChat),
     0x03 :: SetTimesAndDisplay(TitleTimesSpec),
     0x04 :: Hide,
     0x05 :: Reset
@@ -1453,7 +1422,7 @@ proto_varint_enum!(SoundCategory,
 #[derive(Clone, Debug, PartialEq)]
 pub struct StopSoundSpec {
     pub source: Option<SoundCategory>,
-    pub sound: Option<String>,
+    pub sound: Option<Identifier>,
 }
 
 impl Serialize for StopSoundSpec {
@@ -1496,7 +1465,7 @@ impl Deserialize for StopSoundSpec {
         };
 
         let (sound, data) = if is_sound_present {
-            let Deserialized { value: sound, data } = String::mc_deserialize(data)?;
+            let Deserialized { value: sound, data } = Identifier::mc_deserialize(data)?;
             (Some(sound), data)
         } else {
             (None, data)
@@ -1519,7 +1488,7 @@ impl TestRandom for StopSoundSpec {
         };
 
         let sound = if source.is_none() || rand::random::<bool>() {
-            Some(String::test_gen_random())
+            Some(Identifier::test_gen_random())
         } else {
             None
         };
@@ -1679,7 +1648,7 @@ proto_struct!(MapIconSpec {
     kind: MapIconType,
     position: TopDownPosition<i8>,
     direction: i8,
-    display_name: Option<Chat>
+    display_name: Option<LegacyChat>
 });
 
 #[derive(Clone, PartialEq, Debug)]
@@ -1814,7 +1783,7 @@ proto_struct!(CombatEndSpec {
 proto_struct!(CombatEntityDeadSpec {
     player_id: VarInt,
     entity_id: i32,
-    message: Chat
+    message: LegacyChat
 });
 
 proto_struct!(PlayerInfoAction<A> {
@@ -1826,7 +1795,7 @@ proto_varint_enum!(PlayerInfoActionList,
     0x00 :: Add(CountedArray<PlayerInfoAction<PlayerAddActionSpec>, VarInt>),
     0x01 :: UpdateGameMode(CountedArray<PlayerInfoAction<GameMode>, VarInt>),
     0x02 :: UpdateLatency(CountedArray<PlayerInfoAction<VarInt>, VarInt>),
-    0x03 :: UpdateDisplayName(CountedArray<PlayerInfoAction<Option<Chat>>, VarInt>),
+    0x03 :: UpdateDisplayName(CountedArray<PlayerInfoAction<Option<LegacyChat>>, VarInt>),
     0x04 :: Remove(CountedArray<UUID4, VarInt>)
 );
 
@@ -1835,7 +1804,7 @@ proto_struct!(PlayerAddActionSpec {
     properties: CountedArray<PlayerAddProperty, VarInt>,
     game_mode: GameMode,
     ping_ms: VarInt,
-    display_name: Option<Chat>
+    display_name: Option<LegacyChat>
 });
 
 proto_struct!(PlayerAddProperty {
@@ -1844,6 +1813,21 @@ proto_struct!(PlayerAddProperty {
     signature: Option<String>
 });
 
+impl PlayerAddProperty {
+    pub fn textures(&self) -> Result<ProfileTexturesSpec, ProfileTexturesErr> {
+        ProfileTexturesSpec::decode_property(self.name.as_str(), self.value.as_str())
+    }
+
+    // unsigned properties never verify
+    #[cfg(feature = "signatures")]
+    pub fn verify_signature(&self, key: &YggdrasilPublicKey) -> bool {
+        match &self.signature {
+            Some(signature) => key.verify_property(self.value.as_str(), signature.as_str()),
+            None => false,
+        }
+    }
+}
+
 proto_varint_enum!(FacePlayerKind,
     0x00 :: Feet,
     0x01 :: Eyes
@@ -1962,25 +1946,25 @@ proto_varint_enum!(ScoreboardObjectiveKind,
 );
 
 proto_struct!(ScoreboardObjectiveSpec {
-    text: Chat,
+    text: LegacyChat,
     kind: ScoreboardObjectiveKind
 });
 
 proto_struct!(AdvancementMappingEntrySpec {
-    key: String,
+    key: Identifier,
     value: AdvancementSpec
 });
 
 proto_struct!(AdvancementSpec {
-    parent: Option<String>,
+    parent: Option<Identifier>,
     display: Option<AdvancementDisplaySpec>,
     criteria: CountedArray<String, VarInt>,
     requirements: CountedArray<CountedArray<String, VarInt>, VarInt>
 });
 
 proto_struct!(AdvancementDisplaySpec {
-    title: Chat,
-    description: Chat,
+    title: LegacyChat,
+    description: LegacyChat,
     icon: Slot,
     frame_type: AdvancementFrameType,
     flags: AdvancementDisplayFlags,
@@ -1989,7 +1973,7 @@ proto_struct!(AdvancementDisplaySpec {
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct AdvancementDisplayFlags {
-    pub background_texture: Option<String>,
+    pub background_texture: Option<Identifier>,
     pub show_toast: bool,
     pub hidden: bool,
 }
@@ -2024,7 +2008,7 @@ impl Deserialize for AdvancementDisplayFlags {
         let hidden = raw_flags & 0x04 != 0;
 
         Ok(if has_background_texture {
-            String::mc_deserialize(data)?.map(move |id| Some(id))
+            Identifier::mc_deserialize(data)?.map(move |id| Some(id))
         } else {
             Deserialized { value: None, data }
         }.map(move |background_texture| {
@@ -2041,7 +2025,7 @@ impl Deserialize for AdvancementDisplayFlags {
 impl TestRandom for AdvancementDisplayFlags {
     fn test_gen_random() -> Self {
         let background_texture = if rand::random::<bool>() {
-            Some(String::test_gen_random())
+            Some(Identifier::test_gen_random())
         } else {
             None
         };
@@ -2063,7 +2047,7 @@ proto_varint_enum!(AdvancementFrameType,
 );
 
 proto_struct!(AdvancementProgressEntrySpec {
-    key: String,
+    key: Identifier,
     value: AdvancementProgressSpec
 });
 
@@ -2105,7 +2089,7 @@ proto_byte_flag!(EntityEffectFlags,
 );
 
 proto_struct!(TagSpec {
-    name: String,
+    name: Identifier,
     entries: CountedArray<VarInt, VarInt>
 });
 
@@ -2200,7 +2184,7 @@ proto_byte_flag!(SteerVehicleFlags,
 );
 
 proto_varint_enum!(RecipeBookStatus,
-    0x00 :: Displayed(String),
+    0x00 :: Displayed(Identifier),
     0x01 :: States(RecipeBookStates)
 );
 
@@ -2223,7 +2207,7 @@ proto_varint_enum!(ResourcePackStatus,
 );
 
 proto_varint_enum!(AdvancementTabAction,
-    0x00 :: Opened(String),
+    0x00 :: Opened(Identifier),
     0x01 :: Closed
 );
 
@@ -2275,7 +2259,7 @@ proto_byte_flag!(UpdateStructureBlockFlags,
 #[derive(Clone, PartialEq, Debug)]
 pub struct RecipeSpec {
     pub recipe: Recipe,
-    pub id: String,
+    pub id: Identifier,
 }
 
 proto_str_enum!(Recipe,
@@ -2313,13 +2297,14 @@ impl Serialize for RecipeSpec {
 
 impl Deserialize for RecipeSpec {
     fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
-        let Deserialized { value: _type, data } = String::mc_deserialize(data)?;
+        // the type is an identifier too, so "crafting_shaped" means "minecraft:crafting_shaped"
+        let Deserialized { value: _type, data } = Identifier::mc_deserialize(data)?;
         let Deserialized {
             value: recipe_id,
             data,
-        } = String::mc_deserialize(data)?;
+        } = Identifier::mc_deserialize(data)?;
 
-        Ok(Recipe::deserialize_with_id(_type.as_str(), data)?.map(move |recipe| {
+        Ok(Recipe::deserialize_with_id(_type.to_string().as_str(), data)?.map(move |recipe| {
             RecipeSpec {
                 id: recipe_id,
                 recipe,
@@ -2333,7 +2318,7 @@ impl TestRandom for RecipeSpec {
     fn test_gen_random() -> Self {
         RecipeSpec {
             recipe: Recipe::test_gen_random(),
-            id: String::test_gen_random(),
+            id: Identifier::test_gen_random(),
         }
     }
 }
@@ -2844,8 +2829,8 @@ proto_varint_enum!(EntityMetadataFieldData,
     0x01 :: VarInt(VarInt),
     0x02 :: Float(f32),
     0x03 :: String(String),
-    0x04 :: Chat(Chat),
-    0x05 :: OptChat(Option<Chat>),
+    0x04 :: Chat(LegacyChat),
+    0x05 :: OptChat(Option<LegacyChat>),
     0x06 :: Slot(Slot),
     0x07 :: Boolean(bool),
     0x08 :: Rotation(Vec3<f32>),
@@ -3451,6 +3436,51 @@ pub mod tests {
     packet_test_cases!(RawPacket578, Packet578, PlayUseItem, PlayUseItemSpec,
         test_play_use_item, bench_write_play_use_item, bench_read_play_use_item);
 
+    fn chat_message_json(message: Chat) -> (String, PlayServerChatMessageSpec) {
+        use crate::protocol::{RawPacket, HasPacketBody, HasPacketId};
+        let packet = Packet578::PlayServerChatMessage(PlayServerChatMessageSpec {
+            message: message.into(),
+            position: ChatPosition::ChatBox,
+        });
+        let mut out = crate::types::BytesSerializer::default();
+        packet.mc_serialize_body(&mut out).expect("serialize succeeds");
+        let bytes = out.into_bytes();
+        let json = String::mc_deserialize(bytes.as_slice()).expect("starts with a string").value;
+        let raw_packet = RawPacket578::create(packet.id(), bytes.as_slice()).expect("valid id");
+        match raw_packet.deserialize().expect("deserialize succeeds") {
+            Packet578::PlayServerChatMessage(body) => (json, body),
+            other => panic!("expected chat message, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_chat_downsampled_on_wire() {
+        let mut message = Chat::from_text("hi");
+        message.base_mut().color = Some(ChatColor::Rgb(0xFF, 0xB0, 0x00));
+        let (json, body) = chat_message_json(message);
+        assert_eq!(json.as_str(), r#"{"color":"gold","text":"hi"}"#);
+        assert_eq!(body.message.base().color, Some(ChatColor::Legacy(ColorCode::Gold)));
+    }
+
+    #[test]
+    fn test_chat_hover_value_on_wire() {
+        let mut message = Chat::from_text("a");
+        message.base_mut().hover_event = Some(ChatHoverEvent::ShowText(Chat::from_text("tip").boxed()));
+        let mut child = Chat::from_text("b");
+        child.base_mut().hover_event = Some(ChatHoverEvent::ShowItem(HoverItem {
+            id: "minecraft:stone".to_owned(),
+            count: 5,
+            tag: None,
+        }));
+        message.base_mut().extra.push(child.boxed());
+
+        let (json, body) = chat_message_json(message.clone());
+        assert_eq!(json.as_str(), concat!(
+            r#"{"extra":[{"hoverEvent":{"action":"show_item","value":{"text":"{id:\"minecraft:stone\",Count:5b}"}},"text":"b"}],"#,
+            r#""hoverEvent":{"action":"show_text","value":{"text":"tip"}},"text":"a"}"#));
+        assert_eq!(Chat::from(body.message), message);
+    }
+
     // trust me, this is some cutting edge shit
     // I'm definitely not generating code using a unit test
     #[test]
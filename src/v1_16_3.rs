@@ -1,4 +1,4 @@
-use crate::{types::*, uuid::*, *};
+use crate::{types::*, uuid::*, profile::*, *};
 use alloc::{string::{String, ToString}, vec::Vec, borrow::ToOwned, boxed::Box};
 use alloc::fmt;
 use fmt::Debug;
@@ -45,7 +45,7 @@ define_protocol!(753, Packet753, RawPacket753, RawPacket753Body, Packet753Kind =
     },
     LoginPluginRequest, 0x04, Login, ClientBound => LoginPluginRequestSpec {
         message_id: VarInt,
-        channel: String,
+        channel: Identifier,
         data: RemainingBytes
     },
     LoginStart, 0x00, Login, ServerBound => LoginStartSpec {
@@ -181,11 +181,11 @@ define_protocol!(753, Packet753, RawPacket753, RawPacket753Body, Packet753Kind =
         cooldown_ticks: VarInt
     },
     PlayServerPluginMessage, 0x17, Play, ClientBound => PlayServerPluginMessageSpec {
-        channel: String,
+        channel: Identifier,
         data: RemainingBytes
     },
     PlayNamedSoundEffect, 0x18, Play, ClientBound => PlayNamedSoundEffectSpec {
-        sound_name: String,
+        sound_name: Identifier,
         sound_category: SoundCategory,
         position: Vec3<FixedInt>,
         volume: f32,
@@ -245,10 +245,10 @@ define_protocol!(753, Packet753, RawPacket753, RawPacket753Body, Packet753Kind =
         is_hardcore: bool,
         gamemode: GameMode,
         previous_gamemode: PreviousGameMode,
-        worlds: CountedArray<String, VarInt>,
+        worlds: CountedArray<Identifier, VarInt>,
         dimension_codec: NamedNbtTag,
         dimension: NamedNbtTag,
-        world_name: String,
+        world_name: Identifier,
         hashed_seed: i64,
         max_players: VarInt,
         view_distance: VarInt,
@@ -307,7 +307,7 @@ define_protocol!(753, Packet753, RawPacket753, RawPacket753Body, Packet753Kind =
     },
     PlayCraftRecipeResponse, 0x2F, Play, ClientBound => PlayCraftRecipeResponseSpec {
         window_id: u8,
-        recipe: String
+        recipe: Identifier
     },
     PlayServerPlayerAbilities, 0x30, Play, ClientBound => PlayServerPlayerAbilitiesSpec {
         flags: PlayerAbilityFlags,
@@ -340,7 +340,7 @@ define_protocol!(753, Packet753, RawPacket753, RawPacket753Body, Packet753Kind =
         blast_furnace_recipe_book_active: bool,
         smoke_recipe_book_open: bool,
         smoke_recipe_book_active: bool,
-        recipe_ids: CountedArray<String, VarInt>,
+        recipe_ids: CountedArray<Identifier, VarInt>,
         other_recipe_ids: RemainingBytes // todo
     },
     PlayDestroyEntities, 0x36, Play, ClientBound => PlayDestroyEntitiesSpec {
@@ -356,7 +356,7 @@ define_protocol!(753, Packet753, RawPacket753, RawPacket753Body, Packet753Kind =
     },
     PlayRespawn, 0x39, Play, ClientBound => PlayRespawnSpec {
         dimension: NamedNbtTag,
-        world_name: String,
+        world_name: Identifier,
         hashed_seed: i64,
         gamemode: GameMode,
         previous_gamemode: GameMode,
@@ -374,7 +374,7 @@ define_protocol!(753, Packet753, RawPacket753, RawPacket753Body, Packet753Kind =
         blocks: CountedArray<MultiBlockChangeRecord, VarInt>
     },
     PlaySelectAdvancementTab, 0x3C, Play, ClientBound => PlaySelectAdvancementTabSpec {
-        identifier: Option<String>
+        identifier: Option<Identifier>
     },
     PlayWorldBorder, 0x3D, Play, ClientBound => PlayWorldBorderSpec {
         action: WorldBorderAction
@@ -485,7 +485,7 @@ define_protocol!(753, Packet753, RawPacket753, RawPacket753Body, Packet753Kind =
     PlayAdvancements, 0x57, Play, ClientBound => PlayAdvancementsSpec {
         reset: bool,
         mappings: CountedArray<AdvancementMappingEntrySpec, VarInt>,
-        identifiers: CountedArray<String, VarInt>,
+        identifiers: CountedArray<Identifier, VarInt>,
         progress: CountedArray<AdvancementProgressEntrySpec, VarInt>
     },
     PlayEntityProperties, 0x58, Play, ClientBound => PlayEntityPropertiesSpec {
@@ -563,7 +563,7 @@ define_protocol!(753, Packet753, RawPacket753, RawPacket753Body, Packet753Kind =
         window_id: u8
     },
     PlayClientPluginMessage, 0x0B, Play, ServerBound => PlayClientPluginMessageSpec {
-        channel: String,
+        channel: Identifier,
         data: RemainingBytes
     },
     PlayEditBook, 0x0C, Play, ServerBound => PlayEditBookSpec {
@@ -614,7 +614,7 @@ define_protocol!(753, Packet753, RawPacket753, RawPacket753Body, Packet753Kind =
     },
     PlayCraftRecipeRequest, 0x19, Play, ServerBound => PlayCraftRecipeRequestSpec {
         window_id: i8,
-        recipe: String,
+        recipe: Identifier,
         make_all: bool
     },
     PlayClientPlayerAbilities, 0x1A, Play, ServerBound => PlayClientPlayerAbilitiesSpec {
@@ -636,7 +636,7 @@ define_protocol!(753, Packet753, RawPacket753, RawPacket753Body, Packet753Kind =
         flags: SteerVehicleFlags
     },
     PlaySetDisplayedRecipe, 0x1E, Play, ServerBound => PlaySetDisplayedRecipeSpec {
-        recipe_id: String
+        recipe_id: Identifier
     },
     PlaySetRecipeBookState, 0x1F, Play, ServerBound => PlaySetRecipeBookStateSpec {
         book_id: RecipeBookType,
@@ -675,8 +675,8 @@ define_protocol!(753, Packet753, RawPacket753, RawPacket753Body, Packet753Kind =
     },
     PlayUpdateJigsawBlock, 0x28, Play, ServerBound => PlayUpdateJigsawBlockSpec {
         location: IntPosition,
-        attachment_type: String,
-        target_pool: String,
+        attachment_type: Identifier,
+        target_pool: Identifier,
         final_state: String
     },
     PlayCreativeInventoryAction, 0x29, Play, ServerBound => PlayCreativeInventoryActionSpec {
@@ -851,76 +851,7 @@ proto_byte_enum!(ChatPosition,
     0x02 :: Hotbar
 );
 
-#[derive(Copy, Clone, PartialEq, Debug)]
-pub struct BlockChangeHorizontalPosition {
-    pub rel_x: u8,
-    pub rel_z: u8,
-}
-
-impl Serialize for BlockChangeHorizontalPosition {
-    fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
-        to.serialize_byte((self.rel_x & 0xF) << 4 | (self.rel_z & 0xF))
-    }
-}
-
-impl Deserialize for BlockChangeHorizontalPosition {
-    fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
-        Ok(
-            u8::mc_deserialize(data)?.map(move |b| BlockChangeHorizontalPosition {
-                rel_x: (b >> 4) & 0xF,
-                rel_z: b & 0xF,
-            }),
-        )
-    }
-}
-
-#[cfg(all(test, feature = "std"))]
-impl TestRandom for BlockChangeHorizontalPosition {
-    fn test_gen_random() -> Self {
-        BlockChangeHorizontalPosition {
-            rel_x: rand::random::<u8>() % 16,
-            rel_z: rand::random::<u8>() % 16,
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Copy)]
-pub struct ChunkSectionPosition {
-    pub x: i32,
-    pub y: i32,
-    pub z: i32,
-}
-
-impl Serialize for ChunkSectionPosition {
-    fn mc_serialize<S: Serializer>(&self, to: &mut S) -> SerializeResult {
-        let num = (((self.x as u64) & 0x3FFFFF) << 42)
-            | (((self.z as u64) & 0x3FFFFF) << 20)
-            | ((self.y as u64) & 0xFFFFF);
-
-        to.serialize_other(&num)
-    }
-}
-
-impl Deserialize for ChunkSectionPosition {
-    fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
-        let Deserialized { value: raw, data } = u64::mc_deserialize(data)?;
-        let y = (raw & 0xFFFFF) as i32;
-        let z = ((raw >> 20) & 0x3FFFFF) as i32;
-        let x = ((raw >> 42) & 0x3FFFFF) as i32;
-        Deserialized::ok(Self { x, y, z }, data)
-    }
-}
-
-#[cfg(all(test, feature = "std"))]
-impl TestRandom for ChunkSectionPosition {
-    fn test_gen_random() -> Self {
-        Self {
-            x: (u32::test_gen_random() & 0x3FFFFF) as i32,
-            y: (u32::test_gen_random() & 0xFFFFF) as i32,
-            z: (u32::test_gen_random() & 0x3FFFFF) as i32,
-        }
-    }
-}
+pub use crate::types::{BlockChangeHorizontalPosition, ChunkSectionPosition};
 
 #[derive(Debug, Clone, PartialEq, Copy)]
 pub struct MultiBlockChangeRecord {
@@ -1428,7 +1359,7 @@ impl TestRandom for TeamMember {
         if rand_bool {
             Player(String::test_gen_random())
         } else {
-            Entity(UUID4::random())
+            Entity(UUID4::test_gen_random())
         }
     }
 }
@@ -1553,7 +1484,7 @@ proto_varint_enum!(SoundCategory,
 #[derive(Clone, Debug, PartialEq)]
 pub struct StopSoundSpec {
     pub source: Option<SoundCategory>,
-    pub sound: Option<String>,
+    pub sound: Option<Identifier>,
 }
 
 impl Serialize for StopSoundSpec {
@@ -1596,7 +1527,7 @@ impl Deserialize for StopSoundSpec {
         };
 
         let (sound, data) = if is_sound_present {
-            let Deserialized { value: sound, data } = String::mc_deserialize(data)?;
+            let Deserialized { value: sound, data } = Identifier::mc_deserialize(data)?;
             (Some(sound), data)
         } else {
             (None, data)
@@ -1619,7 +1550,7 @@ impl TestRandom for StopSoundSpec {
         };
 
         let sound = if source.is_none() || rand::random::<bool>() {
-            Some(String::test_gen_random())
+            Some(Identifier::test_gen_random())
         } else {
             None
         };
@@ -1993,6 +1924,21 @@ proto_struct!(PlayerAddProperty {
     signature: Option<String>
 });
 
+impl PlayerAddProperty {
+    pub fn textures(&self) -> Result<ProfileTexturesSpec, ProfileTexturesErr> {
+        ProfileTexturesSpec::decode_property(self.name.as_str(), self.value.as_str())
+    }
+
+    // unsigned properties never verify
+    #[cfg(feature = "signatures")]
+    pub fn verify_signature(&self, key: &YggdrasilPublicKey) -> bool {
+        match &self.signature {
+            Some(signature) => key.verify_property(self.value.as_str(), signature.as_str()),
+            None => false,
+        }
+    }
+}
+
 proto_varint_enum!(FacePlayerKind,
     0x00 :: Feet,
     0x01 :: Eyes
@@ -2221,12 +2167,12 @@ proto_struct!(ScoreboardObjectiveSpec {
 });
 
 proto_struct!(AdvancementMappingEntrySpec {
-    key: String,
+    key: Identifier,
     value: AdvancementSpec
 });
 
 proto_struct!(AdvancementSpec {
-    parent: Option<String>,
+    parent: Option<Identifier>,
     display: Option<AdvancementDisplaySpec>,
     criteria: CountedArray<String, VarInt>,
     requirements: CountedArray<CountedArray<String, VarInt>, VarInt>
@@ -2243,7 +2189,7 @@ proto_struct!(AdvancementDisplaySpec {
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct AdvancementDisplayFlags {
-    pub background_texture: Option<String>,
+    pub background_texture: Option<Identifier>,
     pub show_toast: bool,
     pub hidden: bool,
 }
@@ -2278,7 +2224,7 @@ impl Deserialize for AdvancementDisplayFlags {
         let hidden = raw_flags & 0x04 != 0;
 
         Ok(if has_background_texture {
-            String::mc_deserialize(data)?.map(move |id| Some(id))
+            Identifier::mc_deserialize(data)?.map(move |id| Some(id))
         } else {
             Deserialized { value: None, data }
         }.map(move |background_texture| {
@@ -2295,7 +2241,7 @@ impl Deserialize for AdvancementDisplayFlags {
 impl TestRandom for AdvancementDisplayFlags {
     fn test_gen_random() -> Self {
         let background_texture = if rand::random::<bool>() {
-            Some(String::test_gen_random())
+            Some(Identifier::test_gen_random())
         } else {
             None
         };
@@ -2317,7 +2263,7 @@ proto_varint_enum!(AdvancementFrameType,
 );
 
 proto_struct!(AdvancementProgressEntrySpec {
-    key: String,
+    key: Identifier,
     value: AdvancementProgressSpec
 });
 
@@ -2335,7 +2281,7 @@ proto_struct!(AdvancementCriterionProgressSpec {
 });
 
 proto_struct!(EntityPropertySpec {
-    key: String,
+    key: Identifier,
     value: f64,
     modifiers: CountedArray<EntityPropertyModifierSpec, VarInt>
 });
@@ -2359,7 +2305,7 @@ proto_byte_flag!(EntityEffectFlags,
 );
 
 proto_struct!(TagSpec {
-    name: String,
+    name: Identifier,
     entries: CountedArray<VarInt, VarInt>
 });
 
@@ -2468,7 +2414,7 @@ proto_varint_enum!(ResourcePackStatus,
 );
 
 proto_varint_enum!(AdvancementTabAction,
-    0x00 :: Opened(String),
+    0x00 :: Opened(Identifier),
     0x01 :: Closed
 );
 
@@ -2520,7 +2466,7 @@ proto_byte_flag!(UpdateStructureBlockFlags,
 #[derive(Clone, PartialEq, Debug)]
 pub struct RecipeSpec {
     pub recipe: Recipe,
-    pub id: String,
+    pub id: Identifier,
 }
 
 proto_str_enum!(Recipe,
@@ -2559,13 +2505,14 @@ impl Serialize for RecipeSpec {
 
 impl Deserialize for RecipeSpec {
     fn mc_deserialize(data: &[u8]) -> DeserializeResult<'_, Self> {
-        let Deserialized { value: _type, data } = String::mc_deserialize(data)?;
+        // the type is an identifier too, so "crafting_shaped" means "minecraft:crafting_shaped"
+        let Deserialized { value: _type, data } = Identifier::mc_deserialize(data)?;
         let Deserialized {
             value: recipe_id,
             data,
-        } = String::mc_deserialize(data)?;
+        } = Identifier::mc_deserialize(data)?;
 
-        Ok(Recipe::deserialize_with_id(_type.as_str(), data)?.map(move |recipe| {
+        Ok(Recipe::deserialize_with_id(_type.to_string().as_str(), data)?.map(move |recipe| {
             RecipeSpec {
                 id: recipe_id,
                 recipe,
@@ -2579,7 +2526,7 @@ impl TestRandom for RecipeSpec {
     fn test_gen_random() -> Self {
         RecipeSpec {
             recipe: Recipe::test_gen_random(),
-            id: String::test_gen_random(),
+            id: Identifier::test_gen_random(),
         }
     }
 }